@@ -0,0 +1,145 @@
+#ifndef HOOK_ESCAPE_CALCULATOR_H
+#define HOOK_ESCAPE_CALCULATOR_H
+
+/* Generated by cbindgen from src/lib/capi.rs; do not edit by hand. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define HEC_PERK_SLIPPERY_MEAT 0
+
+#define HEC_PERK_UP_THE_ANTE 1
+
+#define HEC_PERK_DELIVERANCE 2
+
+#define HEC_PERK_WICKED 3
+
+/**
+ * Leaves the offering slot empty
+ */
+#define HEC_OFFERING_NONE 0
+
+#define HEC_OFFERING_CHALK_POUCH 1
+
+#define HEC_OFFERING_CREAM_POUCH 2
+
+#define HEC_OFFERING_IVORY_POUCH 3
+
+#define HEC_OFFERING_SALT_POUCH 4
+
+#define HEC_OFFERING_SALT_STATUETTE 5
+
+#define HEC_OFFERING_SALTY_LIPS 6
+
+typedef enum HecStatus {
+  HEC_STATUS_OK = 0,
+  HEC_STATUS_NULL_POINTER,
+  /**
+   * A team has from one to eight survivors
+   */
+  HEC_STATUS_INVALID_SIZE,
+  /**
+   * The survivor index is at or past the size of the team
+   */
+  HEC_STATUS_NOT_IN_TEAM,
+  HEC_STATUS_INVALID_PERK,
+  /**
+   * A perk tier is 1, 2 or 3, or 0 to remove the perk
+   */
+  HEC_STATUS_INVALID_TIER,
+  HEC_STATUS_INVALID_OFFERING,
+  HEC_STATUS_DUPLICATE_PERK,
+  HEC_STATUS_NO_FREE_PERK_SLOT,
+  /**
+   * The odds buffers are shorter than the team
+   */
+  HEC_STATUS_BUFFER_TOO_SMALL,
+} HecStatus;
+
+/**
+ * A team, only ever handled through a pointer
+ */
+typedef struct HecTeam HecTeam;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates a team of `size` empty survivors into `out`, to be freed
+ * with `hec_team_free`.
+ *
+ * # Safety
+ * `out` must be null or valid for a write of one pointer.
+ */
+enum HecStatus hec_team_new(size_t size, struct HecTeam **out);
+
+/**
+ * Frees a team made by `hec_team_new`. Freeing null does nothing.
+ *
+ * # Safety
+ * `team` must be null or a pointer from `hec_team_new` which has not
+ * yet been freed.
+ */
+void hec_team_free(struct HecTeam *team);
+
+/**
+ * # Safety
+ * `team` must be null or a live team, and `out` null or valid for a write.
+ */
+enum HecStatus hec_team_size(const struct HecTeam *team, size_t *out);
+
+/**
+ * Equips one of the `HEC_PERK_` perks at tier 1, 2 or 3, or removes it
+ * at tier 0.
+ *
+ * # Safety
+ * `team` must be null or a live team.
+ */
+enum HecStatus hec_team_set_perk(struct HecTeam *team,
+                                 size_t survivor,
+                                 uint32_t perk,
+                                 uint8_t tier);
+
+/**
+ * Brings one of the `HEC_OFFERING_` offerings, which may be none.
+ *
+ * # Safety
+ * `team` must be null or a live team.
+ */
+enum HecStatus hec_team_set_offering(struct HecTeam *team, size_t survivor, uint32_t offering);
+
+/**
+ * # Safety
+ * `team` must be null or a live team.
+ */
+enum HecStatus hec_team_set_alive(struct HecTeam *team, size_t survivor, bool alive);
+
+/**
+ * # Safety
+ * `team` must be null or a live team.
+ */
+enum HecStatus hec_team_set_lock_condition(struct HecTeam *team, size_t survivor, bool met);
+
+/**
+ * Writes each survivor's chance of escaping on one attempt into
+ * `single` and on any attempt into `total`, by survivor index. Both
+ * buffers must hold at least as many values as the team has survivors.
+ *
+ * # Safety
+ * `team` must be null or a live team, and `single` and `total` null or
+ * valid for writes of `len` doubles.
+ */
+enum HecStatus hec_team_odds(const struct HecTeam *team, double *single, double *total, size_t len);
+
+/**
+ * A description of a status, valid for the life of the program
+ */
+const char *hec_status_message(enum HecStatus status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HOOK_ESCAPE_CALCULATOR_H */
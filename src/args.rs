@@ -0,0 +1,20 @@
+use hook_escape_calculator::ruleset::{self, Ruleset};
+
+const USAGE: &str = "usage: Hook-Escape-Calculator [--rules <file.toml|file.json>]";
+
+/// Reads the command line of the calculator window into the rules file it should use
+pub fn ruleset_from_args() -> Result<Option<Ruleset>, String> {
+    let mut args = std::env::args().skip(1);
+    let mut rules: Option<Ruleset> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => {
+                rules = Some(ruleset::from_path_arg(args.next()).map_err(|e| e.to_string())?)
+            }
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    Ok(rules)
+}
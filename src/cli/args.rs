@@ -0,0 +1,248 @@
+use hook_escape_calculator::{
+    offering::{Offering, OfferingSlot},
+    perk::{PerkName, Tier, TierSlot},
+    ruleset::{self, Ruleset},
+    team::{Team, TeamSize},
+    update::{SurvivorId, SurvivorUpdate},
+};
+
+pub const USAGE: &str = "\
+usage: Hook-Escape-CLI [--rules <file.toml|file.json>] [--json] --survivor <items>...
+
+Each --survivor adds one survivor to the team, up to eight. Their items
+are separated by commas:
+    sm=<1-3>, uta=<1-3>, deliverance=<1-3>, wicked=<1-3>
+                       a luck perk at the given tier
+    offering=<name>    one of chalk-pouch, cream-pouch, ivory-pouch,
+                       salt-pouch, salt-statuette, salty-lips
+    condition          their Deliverance or Wicked condition is met
+    dead               they are no longer in the match
+    none               nothing at all
+
+example: Hook-Escape-CLI --survivor sm=3,uta=2,offering=ivory-pouch --survivor dead";
+
+/// What the command line asks the calculator to do
+pub enum Command {
+    Calculate(Box<Args>),
+    Help,
+}
+
+pub struct Args {
+    pub team: Team,
+    pub rules: Ruleset,
+    pub json: bool,
+}
+
+pub fn command_from_env() -> Result<Command, String> {
+    parse_command(std::env::args().skip(1))
+}
+
+/// Reads the arguments which follow the program name
+fn parse_command(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut rules: Option<Ruleset> = None;
+    let mut json = false;
+    let mut survivors: Vec<String> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => {
+                rules = Some(ruleset::from_path_arg(args.next()).map_err(|e| e.to_string())?)
+            }
+            "--json" => json = true,
+            "--survivor" => survivors.push(args.next().ok_or(USAGE)?),
+            "--help" | "-h" => return Ok(Command::Help),
+            _ => return Err(format!("unknown argument \"{arg}\"\n\n{USAGE}")),
+        }
+    }
+
+    Ok(Command::Calculate(Box::new(Args {
+        team: team_from_specs(&survivors)?,
+        rules: rules.unwrap_or_default(),
+        json,
+    })))
+}
+
+/// Builds a team with one survivor for each spec, in order
+fn team_from_specs(specs: &[String]) -> Result<Team, String> {
+    let size = TeamSize::try_new(specs.len()).map_err(|_| {
+        format!(
+            "a team has one to eight survivors, not {}\n\n{USAGE}",
+            specs.len()
+        )
+    })?;
+    let mut team = Team::with_size(size);
+    for (id, spec) in team.ids().zip(specs) {
+        for update in survivor_updates(id, spec)? {
+            team.alter(update)
+                .map_err(|e| format!("player {}: {e}", *id + 1))?;
+        }
+    }
+    Ok(team)
+}
+
+/// Reads one survivor's comma-separated items into updates of that
+/// survivor. A perk or offering given twice is refused as ambiguous.
+fn survivor_updates(id: SurvivorId, spec: &str) -> Result<Vec<SurvivorUpdate>, String> {
+    let mut perks: Vec<PerkName> = Vec::new();
+    let mut has_offering = false;
+    let items = spec
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty() && *item != "none");
+
+    items
+        .map(|item| {
+            let update = match item.split_once('=') {
+                None => match item {
+                    "dead" => SurvivorUpdate::living_status().id(id).alive(false).call(),
+                    "condition" => SurvivorUpdate::lock_condition().id(id).met(true).call(),
+                    _ => return Err(format!("unknown survivor item \"{item}\"")),
+                },
+                Some(("offering", name)) => {
+                    if std::mem::replace(&mut has_offering, true) {
+                        return Err(String::from("a survivor brings only one offering"));
+                    }
+                    SurvivorUpdate::offering()
+                        .id(id)
+                        .offering(OfferingSlot::new(Some(parse_offering(name)?)))
+                        .call()
+                }
+                Some((perk, tier)) => {
+                    let perk = parse_perk(perk)?;
+                    if perks.contains(&perk) {
+                        return Err(format!("{perk} is given more than once"));
+                    }
+                    perks.push(perk);
+                    SurvivorUpdate::perk()
+                        .id(id)
+                        .perk(perk)
+                        .tier(TierSlot::new(Some(parse_tier(tier)?)))
+                        .call()
+                }
+            };
+            Ok(update)
+        })
+        .collect()
+}
+
+fn parse_perk(name: &str) -> Result<PerkName, String> {
+    match name {
+        "sm" | "slippery-meat" => Ok(PerkName::SlipperyMeat),
+        "uta" | "up-the-ante" => Ok(PerkName::UpTheAnte),
+        "deliverance" => Ok(PerkName::Deliverance),
+        "wicked" => Ok(PerkName::Wicked),
+        _ => Err(format!("unknown luck perk \"{name}\"")),
+    }
+}
+
+fn parse_tier(tier: &str) -> Result<Tier, String> {
+    match tier {
+        "1" => Ok(Tier::One),
+        "2" => Ok(Tier::Two),
+        "3" => Ok(Tier::Three),
+        _ => Err(format!("a perk tier is 1, 2 or 3, not \"{tier}\"")),
+    }
+}
+
+/// Offerings are named as they are displayed, with dashes for spaces
+fn parse_offering(name: &str) -> Result<Offering, String> {
+    Offering::iterator()
+        .find(|x| x.to_string().replace(' ', "-") == name)
+        .ok_or_else(|| format!("unknown offering \"{name}\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_command(args.iter().map(|x| x.to_string()))
+    }
+
+    fn team(args: &[&str]) -> Result<Team, String> {
+        match parse(args)? {
+            Command::Calculate(args) => Ok(args.team),
+            Command::Help => Err(String::from("asked for help")),
+        }
+    }
+
+    fn first() -> SurvivorId {
+        SurvivorId::try_new(0).expect("Zero is a survivor.")
+    }
+
+    #[test]
+    fn items_read_into_survivor() {
+        let team = team(&[
+            "--survivor",
+            " sm=3, uta=2 ,offering=ivory-pouch,condition,dead",
+            "--survivor",
+            "none",
+        ])
+        .expect("Every item is known.");
+        let player = team.get_player(first());
+
+        assert_eq!(*team.size(), 2);
+        assert_eq!(
+            player.get_perk_tier(PerkName::SlipperyMeat),
+            Some(&Tier::Three)
+        );
+        assert_eq!(player.get_perk_tier(PerkName::UpTheAnte), Some(&Tier::Two));
+        assert_eq!(**player.offering(), Some(Offering::IvoryPouch));
+        assert!(player.lock_condition_met() && player.is_dead());
+    }
+
+    #[test]
+    fn unknown_items_refused() {
+        let refused = |spec: &str, error: &str| {
+            let result = team(&["--survivor", spec]);
+            assert!(
+                result.as_ref().is_err_and(|e| e.contains(error)),
+                "{spec} gave {:?}",
+                result.map(|_| ())
+            )
+        };
+
+        refused("lucky", "unknown survivor item \"lucky\"");
+        refused("dh=1", "unknown luck perk \"dh\"");
+        refused("sm=4", "a perk tier is 1, 2 or 3, not \"4\"");
+        refused("uta=", "a perk tier is 1, 2 or 3");
+        refused("offering=salt", "unknown offering \"salt\"");
+    }
+
+    #[test]
+    fn repeated_items_refused() {
+        assert_eq!(
+            team(&["--survivor", "sm=1,slippery-meat=3"]).err(),
+            Some(String::from("slippery meat is given more than once"))
+        );
+        assert_eq!(
+            team(&["--survivor", "offering=salt-pouch,offering=salty-lips"]).err(),
+            Some(String::from("a survivor brings only one offering"))
+        );
+    }
+
+    #[test]
+    fn team_size_bounded() {
+        assert!(team(&[]).is_err_and(|e| e.contains("one to eight survivors, not 0")));
+        let nine: Vec<&str> = ["--survivor", "none"].repeat(9);
+        assert!(team(&nine).is_err_and(|e| e.contains("not 9")));
+    }
+
+    #[test]
+    fn flags_read() {
+        assert!(matches!(
+            parse(&["--survivor", "none", "-h"]),
+            Ok(Command::Help)
+        ));
+        assert!(matches!(parse(&["--help"]), Ok(Command::Help)));
+        assert!(matches!(
+            parse(&["--json", "--survivor", "none"]),
+            Ok(Command::Calculate(args)) if args.json
+        ));
+        assert!(parse(&["--survivor"]).is_err());
+        assert!(parse(&["--rules"]).is_err());
+        assert!(
+            parse(&["--verbose"]).is_err_and(|e| e.starts_with("unknown argument \"--verbose\""))
+        );
+    }
+}
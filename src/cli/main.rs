@@ -0,0 +1,78 @@
+mod args;
+
+use serde::Serialize;
+
+use hook_escape_calculator::{ruleset::Ruleset, team::Team};
+
+/// One survivor's odds, as printed
+#[derive(Serialize)]
+struct SurvivorReport {
+    player: usize,
+    alive: bool,
+    /// The chance of escaping on any one attempt
+    attempt_chance: f64,
+    /// The chance of escaping on exactly each attempt, first attempt first
+    exactly: Vec<f64>,
+    /// The chance of escaping on any attempt
+    total_chance: f64,
+}
+
+fn main() {
+    let args = match args::command_from_env() {
+        Ok(args::Command::Calculate(args)) => *args,
+        Ok(args::Command::Help) => {
+            println!("{}", args::USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2)
+        }
+    };
+
+    let reports = reports(&args.team, &args.rules);
+    if args.json {
+        let text = serde_json::to_string_pretty(&reports)
+            .expect("Reports hold only numbers and booleans.");
+        println!("{text}");
+    } else {
+        for report in &reports {
+            println!("{}", plain_text(report));
+        }
+    }
+}
+
+fn reports(team: &Team, rules: &Ruleset) -> Vec<SurvivorReport> {
+    team.luck_output(rules)
+        .into_iter()
+        .zip(team.escape_distributions(rules))
+        .zip(team.list())
+        .enumerate()
+        .map(
+            |(i, (((attempt_chance, total_chance), distribution), player))| SurvivorReport {
+                player: i + 1,
+                alive: player.is_alive(),
+                attempt_chance,
+                exactly: distribution.approximate().exactly().clone(),
+                total_chance,
+            },
+        )
+        .collect()
+}
+
+fn plain_text(report: &SurvivorReport) -> String {
+    let status = if report.alive { "" } else { " (dead)" };
+    let exactly: Vec<String> = report.exactly.iter().copied().map(format_percent).collect();
+    format!(
+        "Player {}{status}: {} per attempt, {} over all attempts [{}]",
+        report.player,
+        format_percent(report.attempt_chance),
+        format_percent(report.total_chance),
+        exactly.join(", ")
+    )
+}
+
+fn format_percent(num: f64) -> String {
+    let num = num * 100.;
+    format!("{num:.2}%")
+}
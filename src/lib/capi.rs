@@ -0,0 +1,382 @@
+//! A C API over opaque team handles. Every function reports failure
+//! through its `HecStatus`, leaving the team as it was; none of them
+//! panic on bad input. The header is generated into
+//! `include/hook_escape_calculator.h` by `cbindgen` from this file
+//! alone, so that nothing else of the library leaks into the C API.
+
+use std::ffi::c_char;
+
+use strum::EnumIter;
+
+use super::{
+    offering::{Offering, OfferingSlot},
+    perk::{PerkName, Tier, TierSlot},
+    ruleset::Ruleset,
+    team::{Team, TeamSize},
+    update::{SurvivorUpdate, SurvivorUpdateData, UpdateError},
+};
+
+pub const HEC_PERK_SLIPPERY_MEAT: u32 = 0;
+pub const HEC_PERK_UP_THE_ANTE: u32 = 1;
+pub const HEC_PERK_DELIVERANCE: u32 = 2;
+pub const HEC_PERK_WICKED: u32 = 3;
+
+/// Leaves the offering slot empty
+pub const HEC_OFFERING_NONE: u32 = 0;
+pub const HEC_OFFERING_CHALK_POUCH: u32 = 1;
+pub const HEC_OFFERING_CREAM_POUCH: u32 = 2;
+pub const HEC_OFFERING_IVORY_POUCH: u32 = 3;
+pub const HEC_OFFERING_SALT_POUCH: u32 = 4;
+pub const HEC_OFFERING_SALT_STATUETTE: u32 = 5;
+pub const HEC_OFFERING_SALTY_LIPS: u32 = 6;
+
+/// A team, only ever handled through a pointer
+pub struct HecTeam(Team);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum HecStatus {
+    Ok = 0,
+    NullPointer,
+    /// A team has from one to eight survivors
+    InvalidSize,
+    /// The survivor index is at or past the size of the team
+    NotInTeam,
+    InvalidPerk,
+    /// A perk tier is 1, 2 or 3, or 0 to remove the perk
+    InvalidTier,
+    InvalidOffering,
+    DuplicatePerk,
+    NoFreePerkSlot,
+    /// The odds buffers are shorter than the team
+    BufferTooSmall,
+}
+
+/// Creates a team of `size` empty survivors into `out`, to be freed
+/// with `hec_team_free`.
+///
+/// # Safety
+/// `out` must be null or valid for a write of one pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hec_team_new(size: usize, out: *mut *mut HecTeam) -> HecStatus {
+    if out.is_null() {
+        return HecStatus::NullPointer;
+    }
+    let Ok(size) = TeamSize::try_new(size) else {
+        return HecStatus::InvalidSize;
+    };
+    let team = Box::into_raw(Box::new(HecTeam(Team::with_size(size))));
+    // SAFETY: `out` is non-null and the caller promises it is writable
+    unsafe { out.write(team) };
+    HecStatus::Ok
+}
+
+/// Frees a team made by `hec_team_new`. Freeing null does nothing.
+///
+/// # Safety
+/// `team` must be null or a pointer from `hec_team_new` which has not
+/// yet been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hec_team_free(team: *mut HecTeam) {
+    if !team.is_null() {
+        // SAFETY: the caller promises the pointer came from `Box::into_raw` in `hec_team_new`
+        drop(unsafe { Box::from_raw(team) });
+    }
+}
+
+/// # Safety
+/// `team` must be null or a live team, and `out` null or valid for a write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hec_team_size(team: *const HecTeam, out: *mut usize) -> HecStatus {
+    // SAFETY: the caller promises a non-null `team` is live
+    let Some(team) = (unsafe { team.as_ref() }) else {
+        return HecStatus::NullPointer;
+    };
+    if out.is_null() {
+        return HecStatus::NullPointer;
+    }
+    // SAFETY: `out` is non-null and the caller promises it is writable
+    unsafe { out.write(*team.0.size()) };
+    HecStatus::Ok
+}
+
+/// Equips one of the `HEC_PERK_` perks at tier 1, 2 or 3, or removes it
+/// at tier 0.
+///
+/// # Safety
+/// `team` must be null or a live team.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hec_team_set_perk(
+    team: *mut HecTeam,
+    survivor: usize,
+    perk: u32,
+    tier: u8,
+) -> HecStatus {
+    let perk = match perk {
+        HEC_PERK_SLIPPERY_MEAT => PerkName::SlipperyMeat,
+        HEC_PERK_UP_THE_ANTE => PerkName::UpTheAnte,
+        HEC_PERK_DELIVERANCE => PerkName::Deliverance,
+        HEC_PERK_WICKED => PerkName::Wicked,
+        _ => return HecStatus::InvalidPerk,
+    };
+    let tier = match tier {
+        0 => None,
+        1 => Some(Tier::One),
+        2 => Some(Tier::Two),
+        3 => Some(Tier::Three),
+        _ => return HecStatus::InvalidTier,
+    };
+    let update = SurvivorUpdate::perk_usize()
+        .id(survivor)
+        .perk(perk)
+        .tier(TierSlot::new(tier))
+        .call();
+    // SAFETY: the caller promises a non-null `team` is live
+    unsafe { apply(team, update.ok()) }
+}
+
+/// Brings one of the `HEC_OFFERING_` offerings, which may be none.
+///
+/// # Safety
+/// `team` must be null or a live team.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hec_team_set_offering(
+    team: *mut HecTeam,
+    survivor: usize,
+    offering: u32,
+) -> HecStatus {
+    let offering = match offering {
+        HEC_OFFERING_NONE => None,
+        HEC_OFFERING_CHALK_POUCH => Some(Offering::ChalkPouch),
+        HEC_OFFERING_CREAM_POUCH => Some(Offering::CreamPouch),
+        HEC_OFFERING_IVORY_POUCH => Some(Offering::IvoryPouch),
+        HEC_OFFERING_SALT_POUCH => Some(Offering::SaltPouch),
+        HEC_OFFERING_SALT_STATUETTE => Some(Offering::SaltStatuette),
+        HEC_OFFERING_SALTY_LIPS => Some(Offering::SaltyLips),
+        _ => return HecStatus::InvalidOffering,
+    };
+    let update = SurvivorUpdate::offering_usize()
+        .id(survivor)
+        .offering(OfferingSlot::new(offering))
+        .call();
+    // SAFETY: the caller promises a non-null `team` is live
+    unsafe { apply(team, update.ok()) }
+}
+
+/// # Safety
+/// `team` must be null or a live team.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hec_team_set_alive(
+    team: *mut HecTeam,
+    survivor: usize,
+    alive: bool,
+) -> HecStatus {
+    let update = SurvivorUpdate::data_usize()
+        .id(survivor)
+        .update(SurvivorUpdateData::Life(alive))
+        .call();
+    // SAFETY: the caller promises a non-null `team` is live
+    unsafe { apply(team, update.ok()) }
+}
+
+/// # Safety
+/// `team` must be null or a live team.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hec_team_set_lock_condition(
+    team: *mut HecTeam,
+    survivor: usize,
+    met: bool,
+) -> HecStatus {
+    let update = SurvivorUpdate::data_usize()
+        .id(survivor)
+        .update(SurvivorUpdateData::LockCondition(met))
+        .call();
+    // SAFETY: the caller promises a non-null `team` is live
+    unsafe { apply(team, update.ok()) }
+}
+
+/// Writes each survivor's chance of escaping on one attempt into
+/// `single` and on any attempt into `total`, by survivor index. Both
+/// buffers must hold at least as many values as the team has survivors.
+///
+/// # Safety
+/// `team` must be null or a live team, and `single` and `total` null or
+/// valid for writes of `len` doubles.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hec_team_odds(
+    team: *const HecTeam,
+    single: *mut f64,
+    total: *mut f64,
+    len: usize,
+) -> HecStatus {
+    // SAFETY: the caller promises a non-null `team` is live
+    let Some(team) = (unsafe { team.as_ref() }) else {
+        return HecStatus::NullPointer;
+    };
+    if single.is_null() || total.is_null() {
+        return HecStatus::NullPointer;
+    }
+    let odds = team.0.luck_output(&Ruleset::default());
+    if len < odds.len() {
+        return HecStatus::BufferTooSmall;
+    }
+    for (i, (x, y)) in odds.into_iter().enumerate() {
+        // SAFETY: `i` is below `len`, and the caller promises both buffers hold `len` values
+        unsafe {
+            single.add(i).write(x);
+            total.add(i).write(y);
+        }
+    }
+    HecStatus::Ok
+}
+
+/// A description of a status, valid for the life of the program
+#[unsafe(no_mangle)]
+pub extern "C" fn hec_status_message(status: HecStatus) -> *const c_char {
+    let message = match status {
+        HecStatus::Ok => c"ok",
+        HecStatus::NullPointer => c"a required pointer was null",
+        HecStatus::InvalidSize => c"a team has from one to eight survivors",
+        HecStatus::NotInTeam => c"the survivor is not in the team",
+        HecStatus::InvalidPerk => c"there is no such luck perk",
+        HecStatus::InvalidTier => c"a perk tier is 1, 2 or 3, or 0 to remove it",
+        HecStatus::InvalidOffering => c"there is no such offering",
+        HecStatus::DuplicatePerk => c"the perk is already equipped in another perk slot",
+        HecStatus::NoFreePerkSlot => c"every perk slot is already occupied",
+        HecStatus::BufferTooSmall => c"the buffers are shorter than the team",
+    };
+    message.as_ptr()
+}
+
+/// Applies an update, where none means its survivor index was out of range
+///
+/// # Safety
+/// `team` must be null or a live team.
+unsafe fn apply(team: *mut HecTeam, update: Option<SurvivorUpdate>) -> HecStatus {
+    // SAFETY: the caller promises a non-null `team` is live
+    let Some(team) = (unsafe { team.as_mut() }) else {
+        return HecStatus::NullPointer;
+    };
+    let Some(update) = update else {
+        return HecStatus::NotInTeam;
+    };
+    match team.0.alter(update) {
+        Ok(()) => HecStatus::Ok,
+        Err(UpdateError::DuplicatePerk(_)) => HecStatus::DuplicatePerk,
+        Err(UpdateError::NoFreePerkSlot) => HecStatus::NoFreePerkSlot,
+        Err(UpdateError::NotInTeam(_)) => HecStatus::NotInTeam,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A team of `size` made through the C API
+    fn new_team(size: usize) -> *mut HecTeam {
+        let mut team = std::ptr::null_mut();
+        // SAFETY: `team` is a local pointer
+        assert_eq!(unsafe { hec_team_new(size, &mut team) }, HecStatus::Ok);
+        team
+    }
+
+    #[test]
+    fn odds_match_the_library() {
+        let team = new_team(2);
+        let mut single = [0.; 2];
+        let mut total = [0.; 2];
+
+        // SAFETY: `team` is live until freed, and the buffers hold two values
+        unsafe {
+            assert_eq!(
+                hec_team_set_perk(team, 0, HEC_PERK_UP_THE_ANTE, 3),
+                HecStatus::Ok
+            );
+            assert_eq!(
+                hec_team_set_offering(team, 1, HEC_OFFERING_SALTY_LIPS),
+                HecStatus::Ok
+            );
+            assert_eq!(
+                hec_team_odds(team, single.as_mut_ptr(), total.as_mut_ptr(), 2),
+                HecStatus::Ok
+            );
+            let expected = (*team).0.luck_output(&Ruleset::default());
+            assert_eq!(expected, single.into_iter().zip(total).collect::<Vec<_>>());
+            hec_team_free(team);
+        }
+    }
+
+    #[test]
+    fn bad_input_reported_without_change() {
+        let team = new_team(1);
+        let mut out = std::ptr::null_mut();
+        let mut value = [0.; 1];
+
+        // SAFETY: `team` is live until freed, and the buffers hold one value
+        unsafe {
+            let before = (*team).0;
+            assert_eq!(hec_team_new(9, &mut out), HecStatus::InvalidSize);
+            assert_eq!(
+                hec_team_new(1, std::ptr::null_mut()),
+                HecStatus::NullPointer
+            );
+            assert_eq!(hec_team_set_perk(team, 0, 9, 1), HecStatus::InvalidPerk);
+            assert_eq!(
+                hec_team_set_perk(team, 0, HEC_PERK_WICKED, 4),
+                HecStatus::InvalidTier
+            );
+            assert_eq!(
+                hec_team_set_offering(team, 0, 7),
+                HecStatus::InvalidOffering
+            );
+            assert_eq!(hec_team_set_alive(team, 1, false), HecStatus::NotInTeam);
+            assert_eq!(hec_team_set_alive(team, 8, false), HecStatus::NotInTeam);
+            assert_eq!(
+                hec_team_set_alive(std::ptr::null_mut(), 0, false),
+                HecStatus::NullPointer
+            );
+            assert_eq!(
+                hec_team_odds(team, value.as_mut_ptr(), std::ptr::null_mut(), 1),
+                HecStatus::NullPointer
+            );
+            assert_eq!(
+                hec_team_odds(team, value.as_mut_ptr(), value.as_mut_ptr(), 0),
+                HecStatus::BufferTooSmall
+            );
+            assert_eq!((*team).0, before);
+            hec_team_free(team);
+            hec_team_free(std::ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn every_status_has_a_message() {
+        use itertools::Itertools;
+        use strum::IntoEnumIterator;
+
+        let messages: Vec<_> = HecStatus::iter()
+            // SAFETY: status messages are static C strings
+            .map(|x| unsafe { std::ffi::CStr::from_ptr(hec_status_message(x)) })
+            .collect();
+
+        assert_eq!(messages.len(), 10);
+        assert!(messages.iter().all(|x| !x.is_empty()));
+        assert!(messages.iter().all_unique())
+    }
+
+    #[test]
+    fn header_declares_only_the_c_api() {
+        let header = include_str!("../../include/hook_escape_calculator.h");
+        let defines = header
+            .lines()
+            .filter_map(|x| x.strip_prefix("#define "))
+            .filter_map(|x| x.split_whitespace().next());
+
+        for name in defines {
+            assert!(
+                name.starts_with("HEC_") || name == "HOOK_ESCAPE_CALCULATOR_H",
+                "{name} is not part of the C API"
+            );
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use derive_more::Display;
+
+use super::{
+    constants::misc as k,
+    offering::{Offering, OfferingSlot},
+    perk::{Perk, PerkName, PerkSlot, PerkSlotId, Tier},
+    roster::{NamedTeam, Roster, SurvivorLabel},
+    team::{Team, TeamSize},
+    update::{SurvivorUpdate, UpdateError},
+};
+
+/// The layout of the bytes behind a team code. A code of a later
+/// version, or of version 0, is refused rather than misread.
+const CODE_VERSION: u8 = 2;
+/// The first layout, which has no survivor labels. A team with no labels
+/// is still written in it, so that its code stays short.
+const UNLABELLED_VERSION: u8 = 1;
+const CHECKSUM_LEN: usize = 4;
+/// The offering and status of a survivor share one byte
+const OFFERING_MASK: u8 = 0b0000_0111;
+const LOCK_CONDITION_BIT: u8 = 0b0100_0000;
+const DEAD_BIT: u8 = 0b1000_0000;
+const SURVIVOR_LEN: usize = k::MAX_PERKS + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum CodeError {
+    #[display("a team code may only hold letters, digits, '-' and '_'")]
+    InvalidText,
+    #[display("the team code has a typo or is incomplete")]
+    Checksum,
+    #[display("the team code is version {_0}, which this calculator cannot read")]
+    UnsupportedVersion(u8),
+    #[display("the team code does not describe a team")]
+    Malformed,
+    #[display("the team code describes a team which cannot exist: {_0}")]
+    Refused(UpdateError),
+}
+
+impl std::error::Error for CodeError {}
+
+impl Team {
+    /// A short, copy-pasteable text holding every survivor's perks,
+    /// offering, living status and lock condition.
+    pub fn to_code(&self) -> String {
+        encode(team_bytes(self))
+    }
+
+    /// Reads a code written by `to_code` or `NamedTeam::to_code`, ignoring
+    /// surrounding whitespace and any survivor labels
+    pub fn from_code(code: &str) -> Result<Team, CodeError> {
+        NamedTeam::from_code(code).map(|x| x.team)
+    }
+}
+
+impl NamedTeam {
+    /// As `Team::to_code`, followed by each survivor's name and character
+    /// if any survivor in the team is labelled
+    pub fn to_code(&self) -> String {
+        let mut bytes = team_bytes(&self.team);
+        if !self.roster.is_blank_for(&self.team) {
+            bytes[0] = CODE_VERSION;
+            for id in self.team.ids() {
+                let label = self.roster.get(id);
+                push_text(&mut bytes, label.name());
+                push_text(&mut bytes, label.character().as_deref().unwrap_or_default());
+            }
+        }
+        encode(bytes)
+    }
+
+    /// Reads a code written by `to_code` or `Team::to_code`, ignoring
+    /// surrounding whitespace
+    pub fn from_code(code: &str) -> Result<NamedTeam, CodeError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(code.trim())
+            .map_err(|_| CodeError::InvalidText)?;
+        let (body, checksum) = bytes
+            .split_last_chunk::<CHECKSUM_LEN>()
+            .ok_or(CodeError::Checksum)?;
+        if crc32fast::hash(body).to_le_bytes() != *checksum {
+            return Err(CodeError::Checksum);
+        }
+        let [version, size, rest @ ..] = body else {
+            return Err(CodeError::Malformed);
+        };
+        if *version == 0 || *version > CODE_VERSION {
+            return Err(CodeError::UnsupportedVersion(*version));
+        }
+        let size = TeamSize::try_new(usize::from(*size)).map_err(|_| CodeError::Malformed)?;
+        let (survivors, mut labels) = rest
+            .split_at_checked(*size * SURVIVOR_LEN)
+            .ok_or(CodeError::Malformed)?;
+
+        let mut team = Team::with_size(size);
+        for (id, survivor) in team.ids().zip(survivors.chunks_exact(SURVIVOR_LEN)) {
+            let (status, perks) = survivor
+                .split_last()
+                .expect("Survivor chunks are never empty.");
+            let perk_updates = perks.iter().enumerate().map(|(slot, byte)| {
+                let slot =
+                    PerkSlotId::try_new(slot).expect("Chunk of MAX_PERKS indexes below MAX_PERKS.");
+                let perk = perk_from_byte(*byte)?;
+                Ok(SurvivorUpdate::perk_slot()
+                    .id(id)
+                    .slot(slot)
+                    .perk(PerkSlot::new(perk))
+                    .call())
+            });
+            let status_updates = [
+                offering_from_code(status & OFFERING_MASK).map(|x| {
+                    SurvivorUpdate::offering()
+                        .id(id)
+                        .offering(OfferingSlot::new(x))
+                        .call()
+                }),
+                Ok(SurvivorUpdate::lock_condition()
+                    .id(id)
+                    .met(status & LOCK_CONDITION_BIT != 0)
+                    .call()),
+                Ok(SurvivorUpdate::living_status()
+                    .id(id)
+                    .alive(status & DEAD_BIT == 0)
+                    .call()),
+            ];
+            if status & !(OFFERING_MASK | LOCK_CONDITION_BIT | DEAD_BIT) != 0 {
+                return Err(CodeError::Malformed);
+            }
+            for update in perk_updates.chain(status_updates) {
+                team.alter(update?).map_err(CodeError::Refused)?;
+            }
+        }
+
+        let mut roster = Roster::default();
+        if *version >= CODE_VERSION {
+            for id in team.ids() {
+                let name = take_text(&mut labels)?;
+                let character = take_text(&mut labels)?;
+                *roster.get_mut(id) = SurvivorLabel::new(name, Some(character));
+            }
+        }
+        if !labels.is_empty() {
+            return Err(CodeError::Malformed);
+        }
+        Ok(NamedTeam::new(team, roster))
+    }
+}
+
+/// The version, size and survivors of a team, in the unlabelled layout
+fn team_bytes(team: &Team) -> Vec<u8> {
+    let mut bytes = vec![
+        UNLABELLED_VERSION,
+        u8::try_from(*team.size()).expect("Team size is at most TEAM_MAX_CAPACITY."),
+    ];
+    for player in team.list() {
+        for slot in 0..k::MAX_PERKS {
+            let slot = PerkSlotId::try_new(slot).expect("Range below MAX_PERKS is a slot.");
+            bytes.push(perk_byte(player.perk_slot(slot).into_inner()));
+        }
+        let mut status = offering_code(player.offering().into_inner());
+        if player.lock_condition_met() {
+            status |= LOCK_CONDITION_BIT;
+        }
+        if player.is_dead() {
+            status |= DEAD_BIT;
+        }
+        bytes.push(status);
+    }
+    bytes
+}
+
+fn encode(mut bytes: Vec<u8>) -> String {
+    bytes.extend(crc32fast::hash(&bytes).to_le_bytes());
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// A label is its length in bytes followed by its UTF-8
+fn push_text(bytes: &mut Vec<u8>, text: &str) {
+    bytes.push(u8::try_from(text.len()).expect("Labels are at most MAX_LABEL_CHARS characters."));
+    bytes.extend(text.as_bytes());
+}
+
+fn take_text<'a>(bytes: &mut &'a [u8]) -> Result<&'a str, CodeError> {
+    let (len, rest) = bytes.split_first().ok_or(CodeError::Malformed)?;
+    let (text, rest) = rest
+        .split_at_checked(usize::from(*len))
+        .ok_or(CodeError::Malformed)?;
+    *bytes = rest;
+    std::str::from_utf8(text).map_err(|_| CodeError::Malformed)
+}
+
+/// Zero is an empty slot; otherwise the name is in the high bits and the tier in the low two
+fn perk_byte(perk: Option<Perk>) -> u8 {
+    let Some(perk) = perk else {
+        return 0;
+    };
+    let name = match perk.name() {
+        PerkName::SlipperyMeat => 1,
+        PerkName::UpTheAnte => 2,
+        PerkName::Deliverance => 3,
+        PerkName::Wicked => 4,
+        PerkName::Other => 5,
+    };
+    let tier = match perk.tier() {
+        Tier::One => 0,
+        Tier::Two => 1,
+        Tier::Three => 2,
+    };
+    name << 2 | tier
+}
+
+fn perk_from_byte(byte: u8) -> Result<Option<Perk>, CodeError> {
+    if byte == 0 {
+        return Ok(None);
+    }
+    let name = match byte >> 2 {
+        1 => PerkName::SlipperyMeat,
+        2 => PerkName::UpTheAnte,
+        3 => PerkName::Deliverance,
+        4 => PerkName::Wicked,
+        5 => PerkName::Other,
+        _ => return Err(CodeError::Malformed),
+    };
+    let tier = match byte & 0b11 {
+        0 => Tier::One,
+        1 => Tier::Two,
+        2 => Tier::Three,
+        _ => return Err(CodeError::Malformed),
+    };
+    Ok(Some(Perk::new(name, tier)))
+}
+
+fn offering_code(offering: Option<Offering>) -> u8 {
+    match offering {
+        None => 0,
+        Some(Offering::ChalkPouch) => 1,
+        Some(Offering::CreamPouch) => 2,
+        Some(Offering::IvoryPouch) => 3,
+        Some(Offering::SaltPouch) => 4,
+        Some(Offering::SaltStatuette) => 5,
+        Some(Offering::SaltyLips) => 6,
+    }
+}
+
+fn offering_from_code(code: u8) -> Result<Option<Offering>, CodeError> {
+    Ok(Some(match code {
+        0 => return Ok(None),
+        1 => Offering::ChalkPouch,
+        2 => Offering::CreamPouch,
+        3 => Offering::IvoryPouch,
+        4 => Offering::SaltPouch,
+        5 => Offering::SaltStatuette,
+        6 => Offering::SaltyLips,
+        _ => return Err(CodeError::Malformed),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{roster, team};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn named_team_round_trips_through_code(named in roster::arb::named_team()) {
+            let code = named.to_code();
+
+            prop_assert_eq!(NamedTeam::from_code(&code), Ok(named.clone()));
+            prop_assert_eq!(Team::from_code(&code), Ok(named.team))
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn team_round_trips_through_code(team in team::arb::team()) {
+            prop_assert_eq!(Team::from_code(&team.to_code()), Ok(team))
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn typos_are_caught(team in team::arb::team(), position in any::<prop::sample::Index>()) {
+            let code = team.to_code();
+            let i = position.index(code.len());
+            let replacement = if code.as_bytes()[i] == b'A' { "B" } else { "A" };
+            let mut typo = code.clone();
+            typo.replace_range(i..=i, replacement);
+
+            prop_assert!(Team::from_code(&typo).is_err())
+        }
+    }
+
+    #[test]
+    fn default_team_code_is_short() {
+        let code = Team::default().to_code();
+
+        assert!(code.len() <= 40, "{code} is too long to share comfortably");
+        assert_eq!(Team::from_code(&format!("  {code}\n")), Ok(Team::default()))
+    }
+
+    #[test]
+    fn unlabelled_codes_unchanged() {
+        let team = Team::default();
+
+        assert_eq!(
+            NamedTeam::new(team, Roster::default()).to_code(),
+            team.to_code()
+        )
+    }
+
+    #[test]
+    fn newer_versions_refused() {
+        let mut bytes = vec![CODE_VERSION + 1, 1, 0, 0, 0, 0, 0];
+        bytes.extend(crc32fast::hash(&bytes).to_le_bytes());
+
+        assert_eq!(
+            Team::from_code(&URL_SAFE_NO_PAD.encode(bytes)),
+            Err(CodeError::UnsupportedVersion(CODE_VERSION + 1))
+        )
+    }
+
+    #[test]
+    fn version_zero_refused() {
+        let mut bytes = vec![0, 1, 0, 0, 0, 0, 0];
+        bytes.extend(crc32fast::hash(&bytes).to_le_bytes());
+
+        assert_eq!(
+            Team::from_code(&URL_SAFE_NO_PAD.encode(bytes)),
+            Err(CodeError::UnsupportedVersion(0))
+        )
+    }
+}
@@ -1,31 +1,49 @@
-use konst;
+use num_rational::Ratio;
+
+/// Every balance value of the game is a whole percentage, so luck
+/// constants are written as exact fractions of one hundred.
+pub const fn percent(whole: i64) -> Ratio<i64> {
+    Ratio::new_raw(whole, 100)
+}
 
 pub mod offering_luck {
-    pub const SLIGHT_LUCK: f64 = 0.01;
-    pub const MODERATE_LUCK: f64 = 0.02;
-    pub const GREAT_LUCK: f64 = 0.03;
+    use super::*;
+
+    pub const SLIGHT_LUCK: Ratio<i64> = percent(1);
+    pub const MODERATE_LUCK: Ratio<i64> = percent(2);
+    pub const GREAT_LUCK: Ratio<i64> = percent(3);
 }
 
 pub mod perk_luck {
-    pub const UTA_TIER1: f64 = 0.01;
-    pub const UTA_TIER2: f64 = 0.02;
-    pub const UTA_TIER3: f64 = 0.03;
+    use super::*;
+
+    pub const UTA_TIER1: Ratio<i64> = percent(1);
+    pub const UTA_TIER2: Ratio<i64> = percent(2);
+    pub const UTA_TIER3: Ratio<i64> = percent(3);
 
-    pub const SM_TIER1: f64 = 0.02;
-    pub const SM_TIER2: f64 = 0.03;
-    pub const SM_TIER3: f64 = 0.04;
+    pub const SM_TIER1: Ratio<i64> = percent(2);
+    pub const SM_TIER2: Ratio<i64> = percent(3);
+    pub const SM_TIER3: Ratio<i64> = percent(4);
+    pub const SM_ADDITIONAL_UNHOOKS: i8 = 3;
 }
 
 pub mod misc {
     use super::*;
 
-    pub const COUNT_LUCK_PERKS: usize = 2;
     pub const MAX_PERKS: usize = 4;
-    pub const PERKSLOT_COUNT: usize = konst::min!(MAX_PERKS, COUNT_LUCK_PERKS);
 
-    pub const TEAM_MAX_CAPACITY: usize = 4;
+    /// The most survivors a match may have, as in 2v8
+    pub const TEAM_MAX_CAPACITY: usize = 8;
+    pub const DEFAULT_TEAM_SIZE: usize = 4;
+
+    /// The longest survivor or character name kept, in characters
+    pub const MAX_LABEL_CHARS: usize = 32;
+
+    pub const BASE_UNHOOK_CHANCE: Ratio<i64> = percent(4);
+    pub const BASE_UNHOOK_ATTEMPTS: i8 = 3;
 
-    pub const BASE_UNHOOK_CHANCE: f64 = 0.04;
+    /// The luck of a survivor whose Deliverance or Wicked condition is met
+    pub const LOCKED_LUCK: Ratio<i64> = percent(100);
 }
 
 /// Observations are things we know about the universe of the application that the
@@ -46,7 +64,10 @@ pub mod observations {
     pub const MIN_UNHOOK_ATTEMPTS: i8 = 3;
     pub const MAX_UNHOOK_ATTEMPTS: i8 = 6;
 
+    /// The most luck a survivor may have in a team of four, without
+    /// a luck lock. Larger teams may reach certainty through Up the Ante.
     pub const MAX_SINGLE_LUCK: f64 = 0.56;
+    pub const LOCKED_SINGLE_LUCK: f64 = 1.0;
     pub const MIN_SINGLE_LUCK: f64 = 0.04;
     pub const MIN_MULTIPLE_LUCK: f64 = 1. - const_power(1. - MIN_SINGLE_LUCK, MIN_UNHOOK_ATTEMPTS);
     pub const MAX_MULTIPLE_LUCK: f64 = 1. - const_power(1. - MAX_SINGLE_LUCK, MAX_UNHOOK_ATTEMPTS);
@@ -0,0 +1,118 @@
+use derive_getters::Getters;
+use num_traits::{One, ToPrimitive};
+
+use super::luck_record::{Luck, Probability, exact_probability};
+
+/// How a survivor's self-unhook attempts may play out, given the same
+/// luck on every attempt.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct EscapeDistribution {
+    /// The chance of escaping on exactly each attempt, first attempt first
+    exactly: Vec<Probability>,
+    /// The chance of having escaped by each attempt, first attempt first
+    by: Vec<Probability>,
+    /// The chance of using every attempt without escaping
+    never: Probability,
+}
+
+impl EscapeDistribution {
+    pub fn new(luck: Luck, attempts: u8) -> Self {
+        let luck = exact_probability(luck);
+        let chance_fail = Probability::one() - &luck;
+
+        // Before each attempt, the chance of having failed every earlier one
+        let still_hooked: Vec<Probability> =
+            std::iter::successors(Some(Probability::one()), |x| Some(x * &chance_fail))
+                .take(usize::from(attempts) + 1)
+                .collect();
+        let (never, before_each) = still_hooked
+            .split_last()
+            .expect("Successors from Some take at least one item.");
+
+        let exactly = before_each.iter().map(|x| x * &luck).collect();
+        let by = still_hooked[1..]
+            .iter()
+            .map(|x| Probability::one() - x)
+            .collect();
+
+        Self {
+            exactly,
+            by,
+            never: never.clone(),
+        }
+    }
+
+    pub fn attempts(&self) -> usize {
+        self.exactly.len()
+    }
+
+    /// The chance of escaping on any attempt, which is the last entry of `by`
+    pub fn total(&self) -> Probability {
+        Probability::one() - &self.never
+    }
+
+    /// The same distribution, rounded to floating point
+    pub fn approximate(&self) -> ApproximateDistribution {
+        let f = |x: &Probability| {
+            x.to_f64()
+                .expect("A probability is always within the range of f64.")
+        };
+        ApproximateDistribution {
+            exactly: self.exactly.iter().map(f).collect(),
+            by: self.by.iter().map(f).collect(),
+            never: f(&self.never),
+        }
+    }
+}
+
+/// An `EscapeDistribution` rounded to floating point for display
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct ApproximateDistribution {
+    exactly: Vec<f64>,
+    by: Vec<f64>,
+    never: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::percent;
+    use num_traits::Zero;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn outcomes_sum_to_one(hundredths in 0_i64..=100, attempts in 0_u8..=10) {
+            let dist = EscapeDistribution::new(Luck::new(hundredths, 100), attempts);
+            let escaped: Probability = dist.exactly().iter().sum();
+
+            prop_assert_eq!(escaped + dist.never(), Probability::one())
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn by_accumulates_exactly(hundredths in 0_i64..=100, attempts in 0_u8..=10) {
+            let dist = EscapeDistribution::new(Luck::new(hundredths, 100), attempts);
+            let mut running = Probability::zero();
+
+            prop_assert_eq!(dist.attempts(), usize::from(attempts));
+            prop_assert_eq!(dist.by().len(), usize::from(attempts));
+            for (exactly, by) in dist.exactly().iter().zip(dist.by()) {
+                running += exactly;
+                prop_assert_eq!(&running, by);
+            }
+        }
+    }
+
+    #[test]
+    fn base_chance_distribution() {
+        let dist = EscapeDistribution::new(percent(4), 3);
+        let p = |n: i64, d: i64| Probability::new(n.into(), d.into());
+
+        // 4%, then 96% of 4%, then 96% of 96% of 4%
+        assert_eq!(dist.exactly(), &vec![p(1, 25), p(24, 625), p(576, 15_625)]);
+        assert_eq!(dist.never(), &p(24, 25).pow(3));
+        assert_eq!(dist.by().last(), Some(&dist.total()))
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::VecDeque;
+
+use super::roster::NamedTeam;
+
+/// The most edits which may be undone; older edits are forgotten
+pub const HISTORY_CAPACITY: usize = 100;
+
+/// Snapshots of a team and its survivors' names from around each edit,
+/// so that edits may be undone and redone. A named team is small, so a
+/// snapshot is simpler and safer than working out the inverse of each
+/// update, and loading a preset is undone along with the names it brought.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TeamHistory {
+    /// The team before each edit, most recent last
+    undo: VecDeque<NamedTeam>,
+    /// The team after each undone edit, most recently undone last
+    redo: Vec<NamedTeam>,
+}
+
+impl TeamHistory {
+    /// Remembers the team as it was before an edit. A new edit cannot
+    /// be followed by the redo of an older one, so those are forgotten.
+    pub fn record(&mut self, before: NamedTeam) {
+        if self.undo.len() == HISTORY_CAPACITY {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(before);
+        self.redo.clear();
+    }
+
+    /// The team before the last edit, if any, given the team as it is now
+    pub fn undo(&mut self, current: NamedTeam) -> Option<NamedTeam> {
+        let before = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(before)
+    }
+
+    /// The team after the last undone edit, if any, given the team as it is now
+    pub fn redo(&mut self, current: NamedTeam) -> Option<NamedTeam> {
+        let after = self.redo.pop()?;
+        self.undo.push_back(current);
+        Some(after)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roster;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn undo_then_redo_restores_every_team(
+            teams in prop::collection::vec(roster::arb::named_team(), 1..20)
+        ) {
+            let mut history = TeamHistory::default();
+            let mut current = NamedTeam::default();
+            for team in &teams {
+                history.record(current);
+                current = team.clone();
+            }
+            let last = current.clone();
+
+            for expected in teams.iter().rev().skip(1).chain([&NamedTeam::default()]) {
+                current = history.undo(current).expect("Every edit may be undone.");
+                prop_assert_eq!(&current, expected);
+            }
+            prop_assert_eq!(history.undo(current.clone()), None);
+
+            for expected in &teams {
+                current = history.redo(current).expect("Every undone edit may be redone.");
+                prop_assert_eq!(&current, expected);
+            }
+            prop_assert_eq!(&current, &last);
+            prop_assert_eq!(history.redo(current), None);
+        }
+    }
+
+    #[test]
+    fn new_edit_forgets_redo() {
+        let mut history = TeamHistory::default();
+        history.record(NamedTeam::default());
+        let undone = history.undo(NamedTeam::default());
+
+        history.record(NamedTeam::default());
+
+        assert!(undone.is_some());
+        assert!(!history.can_redo())
+    }
+
+    #[test]
+    fn oldest_edits_forgotten() {
+        let mut history = TeamHistory::default();
+        for _ in 0..HISTORY_CAPACITY + 5 {
+            history.record(NamedTeam::default());
+        }
+
+        let undone = std::iter::from_fn(|| history.undo(NamedTeam::default())).count();
+
+        assert_eq!(undone, HISTORY_CAPACITY)
+    }
+}
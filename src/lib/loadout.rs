@@ -3,85 +3,121 @@ use crate::constants::misc as k;
 use super::{
     luck_record::LoadoutLuckRecord,
     offering::OfferingSlot,
-    perk::{Perk, PerkName, PerkSlot},
-    update::{LoadoutUpdate, PerkUpdate},
+    perk::{Perk, PerkName, PerkSlot, PerkSlotId},
+    provenance::LuckItem,
+    ruleset::Ruleset,
+    update::{LoadoutUpdate, PerkSlotUpdate, PerkUpdate, UpdateError},
 };
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Loadout {
-    perks: [PerkSlot; k::PERKSLOT_COUNT],
+    perks: [PerkSlot; k::MAX_PERKS],
     offering: OfferingSlot,
 }
 
 impl Loadout {
-    pub fn alter(&mut self, update: LoadoutUpdate) {
+    pub fn alter(&mut self, update: LoadoutUpdate) -> Result<(), UpdateError> {
         match update {
-            LoadoutUpdate::Offering(x) => self.offering = x,
+            LoadoutUpdate::Offering(x) => {
+                self.offering = x;
+                Ok(())
+            }
             LoadoutUpdate::Perk(x) => self.perk_update(x),
+            LoadoutUpdate::PerkSlot(x) => self.perk_slot_update(x),
+        }
+    }
+    /// Removing a perk by name empties every slot which holds it, while
+    /// equipping one re-tiers its first slot or fills the first free slot.
+    fn perk_update(&mut self, update: PerkUpdate) -> Result<(), UpdateError> {
+        let name = *update.perk();
+        let Some(tier) = update.value().into_inner() else {
+            self.perks
+                .iter_mut()
+                .filter(|slot| slot.is_some_and(|x| *x.name() == name))
+                .for_each(|slot| *slot = PerkSlot::default());
+            return Ok(());
         };
+        let slot = match self.find_perk(name) {
+            Some(slot) => slot,
+            None => self.find_free_slot().ok_or(UpdateError::NoFreePerkSlot)?,
+        };
+
+        self.perks[*slot] = PerkSlot::new(Some(Perk::new(name, tier)));
+        Ok(())
     }
-    fn perk_update(&mut self, update: PerkUpdate) {
-        let perk: &mut PerkSlot = self.get_perk_mut(*update.perk());
-        let new = PerkSlot::new(
-            update
-                .value()
-                .into_inner()
-                .map(|t| Perk::new(*update.perk(), t)),
-        );
+    fn perk_slot_update(&mut self, update: PerkSlotUpdate) -> Result<(), UpdateError> {
+        let slot = *update.slot();
+        let new = *update.value();
+
+        if let Some(perk) = new.into_inner() {
+            let name = *perk.name();
+            let is_duplicate = self
+                .find_perk(name)
+                .is_some_and(|existing| existing != slot);
+            if name.is_luck_perk() && is_duplicate {
+                return Err(UpdateError::DuplicatePerk(name));
+            }
+        }
 
-        *perk = new;
+        self.perks[*slot] = new;
+        Ok(())
     }
 }
 
 // accessors
 impl Loadout {
-    /// Any implementation of perk_label is acceptable so long
-    /// as its inverse function exists, it returns unsigned, and
-    /// tests::all_perks_valid_index passes
-    const fn perk_label(perk: PerkName) -> usize {
-        match perk {
-            PerkName::SlipperyMeat => 0,
-            PerkName::UpTheAnte => 1,
-        }
+    fn find_perk(&self, perk: PerkName) -> Option<PerkSlotId> {
+        self.find_slot(|slot| slot.is_some_and(|x| *x.name() == perk))
+    }
+    fn find_free_slot(&self) -> Option<PerkSlotId> {
+        self.find_slot(|slot| slot.is_none())
+    }
+    fn find_slot(&self, predicate: impl Fn(&PerkSlot) -> bool) -> Option<PerkSlotId> {
+        self.perks.iter().position(predicate).map(|i| {
+            PerkSlotId::try_new(i).expect("Position in array of MAX_PERKS is below MAX_PERKS.")
+        })
+    }
+    /// Returns the first equipped instance of a perk, in whichever slot it sits.
+    pub fn get_perk(&self, perk: PerkName) -> Option<&Perk> {
+        self.equipped_perks().find(|x| *x.name() == perk)
     }
-    pub fn get_perk(&self, perk: PerkName) -> &PerkSlot {
-        let index = Self::perk_label(perk);
-        let expect_msg = format!(
-            "Const index of {:?} is {:?}, which should be <= possible max {}.",
-            perk,
-            index,
-            k::PERKSLOT_COUNT - 1
-        );
-        self.perks.get(index).expect(&expect_msg)
+    pub fn get_perk_slot(&self, slot: PerkSlotId) -> &PerkSlot {
+        &self.perks[*slot]
+    }
+    pub fn equipped_perks(&self) -> impl Iterator<Item = &Perk> + '_ {
+        self.perks.iter().filter_map(|slot| slot.as_ref().as_ref())
     }
     pub fn offering(&self) -> &OfferingSlot {
         &self.offering
     }
 }
 
-// mutable accessors
+// luck collater
 impl Loadout {
-    fn get_perk_mut(&mut self, perk: PerkName) -> &mut PerkSlot {
-        let index = Self::perk_label(perk);
+    pub fn collate_luck(&self, rules: &Ruleset) -> LoadoutLuckRecord {
+        let perk_records = self.equipped_perks().map(|perk| perk.luck_record(rules));
 
-        self.perks
-            .get_mut(index)
-            .expect("{index} ought to be a valid index less than {COUNT_ALL_KNOWN_LUCK_PERKS}")
-    }
-}
+        let offering_luck: LoadoutLuckRecord = self
+            .offering
+            .map(|offering| offering.luck_record(rules))
+            .unwrap_or_default();
 
-// luck collater
-impl Loadout {
-    pub fn collate_luck(&self) -> LoadoutLuckRecord {
+        perk_records.fold(offering_luck, |acc, x| &acc + &x)
+    }
+    /// As `collate_luck`, with each item's share of the luck marked on the
+    /// record. Only luck breakdowns need it, so other callers go without.
+    pub fn collate_luck_traced(&self, rules: &Ruleset) -> LoadoutLuckRecord {
         let perk_records = self
-            .perks
-            .iter()
-            .filter_map(|perk_slot| perk_slot.into_inner())
-            .map(|perk| LoadoutLuckRecord::from(&perk));
+            .equipped_perks()
+            .map(|perk| perk.luck_record(rules).traced(LuckItem::Perk(*perk)));
 
         let offering_luck: LoadoutLuckRecord = self
             .offering
-            .map(|offering| LoadoutLuckRecord::from(&offering))
+            .map(|offering| {
+                offering
+                    .luck_record(rules)
+                    .traced(LuckItem::Offering(offering))
+            })
             .unwrap_or_default();
 
         perk_records.fold(offering_luck, |acc, x| &acc + &x)
@@ -97,38 +133,105 @@ pub mod arb {
 
     prop_compose! {
         pub fn loadout()(
-            perk_names in perk::arb::dinstinct_names(k::PERKSLOT_COUNT),
-            perk_tiers in prop::collection::vec(perk::arb::tier_slot(), k::PERKSLOT_COUNT),
+            perk_slots in prop::collection::vec(perk::arb::perk_slot(), k::MAX_PERKS),
             offering in offering::arb::offering_slot_strategy()
         ) -> Loadout {
-            let iter = perk_names.into_iter().zip(
-                perk_tiers.into_iter()
-            ).map(|(name, tier_slot)| {
-                perk::PerkSlot::new(tier_slot.into_inner().map(|tier| perk::Perk::new(name, tier)))
-            });
-            let mut perks: [PerkSlot; k::PERKSLOT_COUNT] = [PerkSlot::new(None); k::PERKSLOT_COUNT];
-            for (i, perk_slot) in iter.take(k::PERKSLOT_COUNT).enumerate() {
-                perks[i] = perk_slot;
+            let mut loadout = Loadout {
+                offering,
+                ..Loadout::default()
+            };
+            // Duplicate luck perks are refused, leaving their slot empty
+            for (i, perk_slot) in perk_slots.into_iter().enumerate() {
+                let slot = PerkSlotId::try_new(i).expect("vec of MAX_PERKS indexes below MAX_PERKS");
+                let _ = loadout.perk_slot_update(PerkSlotUpdate::new(slot, perk_slot));
             }
 
-            Loadout {
-                perks,
-                offering
-            }
+            loadout
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::perk;
+    use super::super::perk::{Tier, TierSlot};
     use super::*;
     use proptest::prelude::*;
 
+    fn perk_slot(name: PerkName) -> PerkSlot {
+        PerkSlot::new(Some(Perk::new(name, Tier::One)))
+    }
+
+    fn loadout_of(perks: [PerkSlot; k::MAX_PERKS]) -> Result<Loadout, UpdateError> {
+        let mut loadout = Loadout::default();
+        for (slot, perk) in perks.into_iter().enumerate() {
+            let slot =
+                PerkSlotId::try_new(slot).expect("Array of MAX_PERKS indexes below MAX_PERKS.");
+            loadout.alter(LoadoutUpdate::PerkSlot(PerkSlotUpdate::new(slot, perk)))?;
+        }
+        Ok(loadout)
+    }
+
     proptest! {
         #[test]
-        fn all_perks_valid_index(perk in perk::arb::name()) {
-            assert!(Loadout::perk_label(perk) < k::PERKSLOT_COUNT)
+        fn collate_luck_ignores_slot_order(loadout in arb::loadout()) {
+            let mut reversed = loadout.perks;
+            reversed.reverse();
+            let reversed = Loadout {
+                offering: loadout.offering,
+                ..loadout_of(reversed).expect("Reordering a valid loadout keeps it valid.")
+            };
+            let rules = Ruleset::default();
+            let (a, b) = (loadout.collate_luck(&rules), reversed.collate_luck(&rules));
+
+            prop_assert_eq!(a.personal(), b.personal());
+            prop_assert_eq!(a.global(), b.global());
+            prop_assert_eq!(a.up_the_ante_coeff(), b.up_the_ante_coeff());
+            prop_assert_eq!(a.additional_unhooks(), b.additional_unhooks());
+            prop_assert_eq!(a.conditional_lock(), b.conditional_lock());
         }
     }
+
+    #[test]
+    fn duplicate_luck_perks_refused() {
+        let perks = [
+            perk_slot(PerkName::UpTheAnte),
+            PerkSlot::default(),
+            perk_slot(PerkName::UpTheAnte),
+            PerkSlot::default(),
+        ];
+
+        assert_eq!(
+            loadout_of(perks),
+            Err(UpdateError::DuplicatePerk(PerkName::UpTheAnte))
+        )
+    }
+
+    #[test]
+    fn duplicate_other_perks_allowed() {
+        let perks = [perk_slot(PerkName::Other); k::MAX_PERKS];
+
+        assert!(loadout_of(perks).is_ok())
+    }
+
+    #[test]
+    fn perk_update_needs_free_slot() {
+        let perks = [perk_slot(PerkName::Other); k::MAX_PERKS];
+        let mut loadout = loadout_of(perks).expect("Other may be equipped repeatedly.");
+        let update = LoadoutUpdate::Perk(PerkUpdate::new(
+            PerkName::SlipperyMeat,
+            TierSlot::new(Some(Tier::Three)),
+        ));
+
+        assert_eq!(loadout.alter(update), Err(UpdateError::NoFreePerkSlot))
+    }
+
+    #[test]
+    fn perk_removal_empties_every_copy() {
+        let perks = [perk_slot(PerkName::Other); k::MAX_PERKS];
+        let mut loadout = loadout_of(perks).expect("Other may be equipped repeatedly.");
+        let update = LoadoutUpdate::Perk(PerkUpdate::new(PerkName::Other, TierSlot::new(None)));
+
+        assert_eq!(loadout.alter(update), Ok(()));
+        assert_eq!(loadout.equipped_perks().count(), 0)
+    }
 }
@@ -1,29 +1,93 @@
 use arrayvec::ArrayVec;
 use derive_getters::Getters;
 use itertools::Either;
-
-use super::{constants::misc::TEAM_MAX_CAPACITY, living_count::LivingCount};
-
-pub type Luck = f64;
+use num_bigint::BigInt;
+use num_rational::{BigRational, Ratio};
+use num_traits::{One, Zero};
+
+use super::{
+    constants::misc::{LOCKED_LUCK, MAX_PERKS, TEAM_MAX_CAPACITY},
+    distribution::EscapeDistribution,
+    living_count::LivingCount,
+    perk::PerkName,
+    provenance::{LuckBreakdown, LuckContribution, LuckItem, LuckSource},
+    update::SurvivorId,
+};
+
+/// Luck is kept as an exact fraction. Every balance value is a whole
+/// percentage, so sums of luck never lose precision.
+pub type Luck = Ratio<i64>;
+
+/// The chance of escaping across several attempts, which is a power of
+/// luck. Its denominator outgrows any machine integer, so it is unbounded.
+pub type Probability = BigRational;
+
+pub fn exact_probability(luck: Luck) -> Probability {
+    BigRational::new(BigInt::from(*luck.numer()), BigInt::from(*luck.denom()))
+}
 
 /// A record that represents a players luck items such that
 /// two personal lucks are summed rather than list appended
-#[derive(PartialEq, Debug, Clone, Copy, Getters)]
+#[derive(Debug, Clone, Copy, Getters)]
 pub struct LoadoutLuckRecord {
     personal: Luck,
     global: Luck,
     up_the_ante_coeff: Option<Luck>,
     additional_unhooks: i8,
+    conditional_lock: bool,
+    /// The items behind the luck, if the record was traced. They do not
+    /// take part in equality, since two records of the same luck are alike.
+    sources: LoadoutSources,
+}
+
+/// One item's share of a loadout's luck
+#[derive(PartialEq, Debug, Clone, Copy, Getters)]
+pub struct ItemLuck {
+    item: LuckItem,
+    personal: Luck,
+    global: Luck,
+    conditional_lock: bool,
+}
+
+/// The items behind a loadout's luck, in the order they were added. It
+/// is a fixed array rather than a list so that records stay `Copy`.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct LoadoutSources([Option<ItemLuck>; MAX_PERKS + 1]);
+
+impl LoadoutSources {
+    const fn const_default() -> Self {
+        Self([None; MAX_PERKS + 1])
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &ItemLuck> + '_ {
+        self.0.iter().flatten()
+    }
+    /// The sources of both, which fit since a loadout holds at most one
+    /// offering and `MAX_PERKS` perks. Any more are forgotten.
+    fn chain(&self, other: &Self) -> Self {
+        let mut chained = Self::const_default();
+        for (slot, item) in chained.0.iter_mut().zip(self.iter().chain(other.iter())) {
+            *slot = Some(*item);
+        }
+        chained
+    }
+}
+
+impl std::default::Default for LoadoutSources {
+    fn default() -> Self {
+        Self::const_default()
+    }
 }
 
 /// Init methods
 impl LoadoutLuckRecord {
     const fn const_default() -> Self {
         Self {
-            personal: 0.0,
-            global: 0.0,
+            personal: Ratio::new_raw(0, 1),
+            global: Ratio::new_raw(0, 1),
             up_the_ante_coeff: None,
             additional_unhooks: 0,
+            conditional_lock: false,
+            sources: LoadoutSources::const_default(),
         }
     }
     pub const fn with_personal(personal: Luck) -> Self {
@@ -50,6 +114,29 @@ impl LoadoutLuckRecord {
             ..Self::const_default()
         }
     }
+    /// A record of an item which locks luck at 100% when its
+    /// in-game condition is met, such as Deliverance or Wicked.
+    pub const fn with_luck_lock() -> Self {
+        Self {
+            conditional_lock: true,
+            ..Self::const_default()
+        }
+    }
+    /// The same record, marked as the luck of one item. A record without
+    /// luck is left unmarked, since its item adds nothing to explain.
+    pub fn traced(self, item: LuckItem) -> Self {
+        if self == Self::default() {
+            return self;
+        }
+        let mut sources = LoadoutSources::const_default();
+        sources.0[0] = Some(ItemLuck {
+            item,
+            personal: self.personal,
+            global: self.global,
+            conditional_lock: self.conditional_lock,
+        });
+        Self { sources, ..self }
+    }
 }
 
 impl std::default::Default for LoadoutLuckRecord {
@@ -58,6 +145,16 @@ impl std::default::Default for LoadoutLuckRecord {
     }
 }
 
+impl PartialEq for LoadoutLuckRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.personal == other.personal
+            && self.global == other.global
+            && self.up_the_ante_coeff == other.up_the_ante_coeff
+            && self.additional_unhooks == other.additional_unhooks
+            && self.conditional_lock == other.conditional_lock
+    }
+}
+
 /// An add instance for `LoadoutLuckRecord`. It is assumed that
 /// the input player luck items does not contain multiple instances
 /// of Up the Ante. If there are, the second is discarded.
@@ -70,6 +167,8 @@ impl std::ops::Add for &LoadoutLuckRecord {
             global: self.global + other.global,
             up_the_ante_coeff: self.up_the_ante_coeff.or(other.up_the_ante_coeff),
             additional_unhooks: self.additional_unhooks + other.additional_unhooks,
+            conditional_lock: self.conditional_lock || other.conditional_lock,
+            sources: self.sources.chain(&other.sources),
         }
     }
 }
@@ -77,11 +176,15 @@ impl std::ops::Add for &LoadoutLuckRecord {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct LoadoutPlayerConverter {
     is_alive: bool,
+    lock_condition_met: bool,
 }
 
 impl LoadoutPlayerConverter {
-    pub const fn new(is_alive: bool) -> Self {
-        Self { is_alive }
+    pub const fn new(is_alive: bool, lock_condition_met: bool) -> Self {
+        Self {
+            is_alive,
+            lock_condition_met,
+        }
     }
     pub const fn convert(&self, loadout: LoadoutLuckRecord) -> PlayerLuckRecord {
         let LoadoutLuckRecord {
@@ -89,6 +192,8 @@ impl LoadoutPlayerConverter {
             global,
             mut up_the_ante_coeff,
             additional_unhooks,
+            conditional_lock,
+            sources,
         } = loadout;
         // This line is what causes dead players to not contribute their
         // Up the Ante to the global luck.
@@ -102,37 +207,53 @@ impl LoadoutPlayerConverter {
             global,
             up_the_ante_coeff,
             additional_unhooks,
+            conditional_lock: conditional_lock && self.lock_condition_met,
+            sources,
         })
     }
 }
 
+/// A loadout's luck once the player's state is known. Its sources are
+/// those of the loadout, while its Up the Ante and lock say whether those
+/// items still count.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PlayerLuckRecord(pub LoadoutLuckRecord);
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PlayerTeamConverter {
     living_other_than_self_count: LivingCount,
+    /// The survivor whose record is converted, to name in the sources
+    owner: Option<SurvivorId>,
 }
 
 impl PlayerTeamConverter {
     pub const fn new(living_other_than_self_count: LivingCount) -> Self {
         Self {
             living_other_than_self_count,
+            owner: None,
+        }
+    }
+    pub const fn owned_by(self, owner: SurvivorId) -> Self {
+        Self {
+            owner: Some(owner),
+            ..self
         }
     }
+    /// The survivor's luck as part of the team, without its sources
     pub fn convert(&self, plr: &PlayerLuckRecord) -> TeamLuckRecord {
         let LoadoutLuckRecord {
             personal,
             global,
-            up_the_ante_coeff,
             additional_unhooks,
+            conditional_lock,
+            ..
         } = plr.0;
 
-        let uta_contribution = up_the_ante_coeff.map_or(0.0, |x| {
-            x * f64::from(self.living_other_than_self_count.into_inner())
-        });
-
-        let final_global = global + uta_contribution;
+        let final_global = global + self.uta_contribution(&plr.0);
+        let personal = match conditional_lock {
+            true => LOCKED_LUCK,
+            false => personal,
+        };
         let personal_data = {
             let mut personal_data = ArrayVec::new();
             personal_data.push((personal, additional_unhooks));
@@ -142,44 +263,172 @@ impl PlayerTeamConverter {
         TeamLuckRecord {
             global: final_global,
             personals: Some(personal_data),
+            ..TeamLuckRecord::default()
         }
     }
+    /// As `convert`, with where the survivor's luck came from
+    pub fn convert_traced(&self, plr: &PlayerLuckRecord) -> TeamLuckRecord {
+        let uta_contribution = self.uta_contribution(&plr.0);
+        TeamLuckRecord {
+            global_sources: self.global_sources(&plr.0, uta_contribution),
+            personal_sources: vec![self.personal_sources(&plr.0)],
+            ..self.convert(plr)
+        }
+    }
+    fn uta_contribution(&self, record: &LoadoutLuckRecord) -> Luck {
+        record.up_the_ante_coeff.map_or(Luck::zero(), |x| {
+            x * i64::from(self.living_other_than_self_count.into_inner())
+        })
+    }
+    fn item_contributions(
+        &self,
+        sources: &LoadoutSources,
+        luck: impl Fn(&ItemLuck) -> Luck,
+    ) -> impl Iterator<Item = LuckContribution> {
+        let owner = self.owner;
+        sources.iter().filter_map(move |x| {
+            let luck = luck(x);
+            let source = LuckSource::Item {
+                owner,
+                item: x.item,
+            };
+            (!luck.is_zero()).then_some(LuckContribution::new(source, luck))
+        })
+    }
+    /// Up the Ante is listed even when nobody else is alive, to show why it gives nothing
+    fn global_sources(&self, record: &LoadoutLuckRecord, uta: Luck) -> Vec<LuckContribution> {
+        let up_the_ante = record.up_the_ante_coeff.map(|_| {
+            let source = LuckSource::UpTheAnte {
+                owner: self.owner,
+                living_others: self.living_other_than_self_count.into_inner(),
+            };
+            LuckContribution::new(source, uta)
+        });
+        self.item_contributions(&record.sources, |x| x.global)
+            .chain(up_the_ante)
+            .collect()
+    }
+    /// A met lock replaces every personal source with itself
+    fn personal_sources(&self, record: &LoadoutLuckRecord) -> Vec<LuckContribution> {
+        if !record.conditional_lock {
+            return self
+                .item_contributions(&record.sources, |x| x.personal)
+                .collect();
+        }
+        let lock = record.sources.iter().find_map(|x| match x.item {
+            LuckItem::Perk(perk) if x.conditional_lock => Some(*perk.name()),
+            _ => None,
+        });
+        lock.map(|perk: PerkName| LuckContribution::new(LuckSource::Lock(perk), LOCKED_LUCK))
+            .into_iter()
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct TeamLuckRecord {
     global: Luck,
     personals: Option<ArrayVec<(Luck, i8), { TEAM_MAX_CAPACITY }>>,
+    /// Where the global luck came from, as far as it is known
+    global_sources: Vec<LuckContribution>,
+    /// Where each survivor's personal luck came from, in the order of `personals`
+    personal_sources: Vec<Vec<LuckContribution>>,
 }
 
 impl TeamLuckRecord {
     const fn const_default() -> Self {
         TeamLuckRecord {
-            global: 0.0,
+            global: Ratio::new_raw(0, 1),
             personals: None,
+            global_sources: Vec::new(),
+            personal_sources: Vec::new(),
         }
     }
     pub const fn with_global(luck: Luck) -> Self {
         TeamLuckRecord {
             global: luck,
             personals: None,
+            global_sources: Vec::new(),
+            personal_sources: Vec::new(),
+        }
+    }
+    /// Global luck which is the base chance of every survivor
+    pub fn with_base(luck: Luck) -> Self {
+        TeamLuckRecord {
+            global_sources: vec![LuckContribution::new(LuckSource::Base, luck)],
+            ..Self::with_global(luck)
         }
     }
+    /// Where each survivor's luck came from. Luck the sources do not
+    /// account for is listed as untraced, so each breakdown sums to the
+    /// luck of `luck_unhook_mod_pairs_iter`.
+    pub fn luck_breakdowns(&self) -> impl Iterator<Item = LuckBreakdown> + '_ {
+        let untraced = |luck: Luck, sources: &[LuckContribution]| {
+            let rest = sources.iter().fold(luck, |acc, x| acc - x.luck());
+            (!rest.is_zero()).then_some(LuckContribution::new(LuckSource::Untraced, rest))
+        };
+        let global = untraced(self.global, &self.global_sources);
+        self.personals
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(move |(i, (personal, _))| {
+                let personal_sources = self.personal_sources.get(i).map_or(&[][..], Vec::as_slice);
+                let mut contributions: Vec<LuckContribution> = self
+                    .global_sources
+                    .iter()
+                    .copied()
+                    .chain(global)
+                    .chain(personal_sources.iter().copied())
+                    .chain(untraced(*personal, personal_sources))
+                    .collect();
+                let luck = personal + self.global;
+                if luck > LOCKED_LUCK {
+                    contributions.push(LuckContribution::new(LuckSource::Cap, LOCKED_LUCK - luck));
+                }
+                LuckBreakdown::new(contributions)
+            })
+    }
     pub fn luck_unhook_mod_pairs_iter(&self) -> impl Iterator<Item = (Luck, i8)> + '_ {
         match &self.personals {
-            Some(personals) => Either::Left(personals.iter().map(|(l, u)| (l + self.global, *u))),
-            None => Either::Right(std::iter::empty::<(f64, i8)>()),
+            // Luck is a probability, so no amount of global luck may push a
+            // survivor, locked or otherwise, past certainty.
+            Some(personals) => Either::Left(
+                personals
+                    .iter()
+                    .map(|(l, u)| ((l + self.global).min(LOCKED_LUCK), *u)),
+            ),
+            None => Either::Right(std::iter::empty::<(Luck, i8)>()),
         }
     }
-    pub fn make_single_and_total_unhook_pairs(&self) -> impl Iterator<Item = (Luck, Luck)> + '_ {
+    pub fn make_single_and_total_unhook_pairs(
+        &self,
+        base_unhook_attempts: i8,
+    ) -> impl Iterator<Item = (Luck, Probability)> + '_ {
         self.luck_unhook_mod_pairs_iter()
-            .map(|(luck, unhook_count)| {
-                let chance_fail: Luck = 1.0 - luck;
-                let chance_fail_all = chance_fail.powi(i32::from(unhook_count) + 3);
-                let chance_succeed_once = 1.0 - chance_fail_all;
+            .map(move |(luck, unhook_count)| {
+                let chance_fail: Probability = Probability::one() - exact_probability(luck);
+                let attempts = total_attempts(unhook_count, base_unhook_attempts);
+                let chance_fail_all = chance_fail.pow(i32::from(attempts));
+                let chance_succeed_once = Probability::one() - chance_fail_all;
                 (luck, chance_succeed_once)
             })
     }
+    pub fn make_escape_distributions(
+        &self,
+        base_unhook_attempts: i8,
+    ) -> impl Iterator<Item = EscapeDistribution> + '_ {
+        self.luck_unhook_mod_pairs_iter()
+            .map(move |(luck, unhook_count)| {
+                EscapeDistribution::new(luck, total_attempts(unhook_count, base_unhook_attempts))
+            })
+    }
+}
+
+/// No amount of lost attempts leaves a survivor with fewer than none
+pub(crate) fn total_attempts(unhook_count: i8, base_unhook_attempts: i8) -> u8 {
+    let attempts = (i16::from(unhook_count) + i16::from(base_unhook_attempts)).max(0);
+    u8::try_from(attempts).expect("The sum of two i8 values is below 256.")
 }
 
 impl std::default::Default for TeamLuckRecord {
@@ -200,7 +449,7 @@ impl std::ops::Add for &TeamLuckRecord {
                     left.len() + right.len() <= TEAM_MAX_CAPACITY,
                     "Wanted to combine to ArrayVecs with combined length >TEAM_MAX_CAPACITY"
                 );
-                let mut left: ArrayVec<(f64, i8), TEAM_MAX_CAPACITY> = left.clone();
+                let mut left: ArrayVec<(Luck, i8), TEAM_MAX_CAPACITY> = left.clone();
                 left.extend(right.clone());
                 Some(left)
             }
@@ -209,13 +458,15 @@ impl std::ops::Add for &TeamLuckRecord {
         TeamLuckRecord {
             global: self.global + other.global,
             personals,
+            global_sources: [&self.global_sources[..], &other.global_sources[..]].concat(),
+            personal_sources: [&self.personal_sources[..], &other.personal_sources[..]].concat(),
         }
     }
 }
 
 #[cfg(test)]
 mod arb {
-    use super::super::{offering::arb::offering, perk::arb::perk, team};
+    use super::super::{offering::arb::offering, perk::arb::perk, ruleset::Ruleset, team};
     use super::*;
     use proptest::prelude::*;
 
@@ -245,15 +496,15 @@ mod arb {
     prop_compose! {
         pub fn single_loadout_luck_record()(item in loadout_item()) -> LoadoutLuckRecord {
             match item {
-                LuckItem::Perk(p) => LoadoutLuckRecord::from(&p),
-                LuckItem::Offering(o) => LoadoutLuckRecord::from(&o)
+                LuckItem::Perk(p) => p.luck_record(&Ruleset::default()),
+                LuckItem::Offering(o) => o.luck_record(&Ruleset::default())
             }
         }
     }
 
     prop_compose! {
-        pub fn prob()(luck in 0.0_f64..1.0_f64) -> f64 {
-            luck
+        pub fn prob()(hundredths in 0_i64..=100) -> Luck {
+            Ratio::new(hundredths, 100)
         }
     }
 
@@ -269,11 +520,8 @@ mod arb {
 mod tests {
     use super::*;
     use crate::constants::*;
-    use float_cmp::assert_approx_eq;
     use proptest::prelude::*;
 
-    const EPSILON_FOUR_SIG_DIGITS: f64 = 0.00001;
-
     fn loadout_records_not_overflow(xs: &[LoadoutLuckRecord]) -> bool {
         (-128_i16..128_i16).contains(&xs.iter().map(|x| x.additional_unhooks() as i16).sum())
     }
@@ -285,7 +533,7 @@ mod tests {
 
         proptest! {
             #[test]
-            fn personal_sets_only_personal(p in 0.0_f64..1.0_f64) {
+            fn personal_sets_only_personal(p in arb::prob()) {
                 let result = LoadoutLuckRecord::with_personal(p);
 
                 prop_assert_eq!(*result.personal(), p);
@@ -296,7 +544,7 @@ mod tests {
         }
         proptest! {
             #[test]
-            fn global_sets_only_global(g in 0.0_f64..1.0_f64) {
+            fn global_sets_only_global(g in arb::prob()) {
                 let result = LoadoutLuckRecord::with_global(g);
 
                 prop_assert_eq!(*result.global(), g);
@@ -307,7 +555,7 @@ mod tests {
         }
         proptest! {
             #[test]
-            fn uta_sets_only_uta(c in 0.0_f64..1.0_f64) {
+            fn uta_sets_only_uta(c in arb::prob()) {
                 let result = LoadoutLuckRecord::with_uta(c);
 
                 prop_assert_eq!(result.up_the_ante_coeff(), &Some(c));
@@ -369,17 +617,15 @@ mod tests {
                 prop_assume! (loadout_records_not_overflow(&[a, b, c]));
                 let (a, b, c) = (&a, &b, &c);
                 let (left, right) = (&(a + b) + c, a + &(b + c));
-                let p_test = |&l, &r| assert_approx_eq!(f64, l, r);
-
-                p_test(left.global(), right.global());
-                p_test(left.personal(), right.personal());
+                prop_assert_eq!(left.global(), right.global());
+                prop_assert_eq!(left.personal(), right.personal());
                 prop_assert_eq!(left.up_the_ante_coeff(), right.up_the_ante_coeff());
                 prop_assert_eq!(left.additional_unhooks(), right.additional_unhooks())
             }
         }
         proptest! {
             #[test]
-            fn ante_prefers_left(a in 0.0_f64..1.0_f64, b in 0.0_f64..1.0) {
+            fn ante_prefers_left(a in arb::prob(), b in arb::prob()) {
                 let left = LoadoutLuckRecord {
                     up_the_ante_coeff: Some(a),
                     ..LoadoutLuckRecord::default()
@@ -403,6 +649,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn equality_ignores_sources() {
+        let record = LoadoutLuckRecord::with_personal(offering_luck::GREAT_LUCK);
+        let traced = record.traced(LuckItem::Offering(crate::offering::Offering::IvoryPouch));
+
+        assert!(traced.sources().iter().next().is_some());
+        assert_eq!(record, traced)
+    }
+
+    proptest! {
+        #[test]
+        fn odds_gather_no_sources(team in arb::real_team_record()) {
+            prop_assert_eq!(team.global_sources.capacity(), 0);
+            prop_assert_eq!(team.personal_sources.capacity(), 0)
+        }
+    }
+
     fn altruistic_team() -> TeamLuckRecord {
         let mut personals = ArrayVec::new();
         for _ in 0..3 {
@@ -413,54 +676,78 @@ mod tests {
 
         &global_team_luck_record
             + (&TeamLuckRecord {
-                global: perk_luck::UTA_TIER3 * 3.0 * 3.0 + offering_luck::GREAT_LUCK * 3.0,
+                global: perk_luck::UTA_TIER3 * 3 * 3 + offering_luck::GREAT_LUCK * 3,
                 personals: Some(personals),
+                ..TeamLuckRecord::default()
             })
     }
     // Note that this test is impossible
     #[test]
     fn integrated_combine() {
         let a = LoadoutLuckRecord {
-            personal: 0.04,
-            global: 0.01,
-            up_the_ante_coeff: Some(0.02),
+            personal: percent(4),
+            global: percent(1),
+            up_the_ante_coeff: Some(percent(2)),
             additional_unhooks: 0,
+            conditional_lock: false,
+            ..LoadoutLuckRecord::default()
         };
         let b = LoadoutLuckRecord {
-            personal: 0.02,
-            global: 0.02,
+            personal: percent(2),
+            global: percent(2),
             up_the_ante_coeff: None,
             additional_unhooks: 3,
+            conditional_lock: true,
+            ..LoadoutLuckRecord::default()
         };
         let c = &a + &b;
-        assert_approx_eq!(f64, c.personal, 0.06, epsilon = EPSILON_FOUR_SIG_DIGITS);
-        assert_approx_eq!(f64, c.global, 0.03, epsilon = EPSILON_FOUR_SIG_DIGITS);
-        assert_approx_eq!(
-            f64,
-            c.up_the_ante_coeff.unwrap(),
-            0.02,
-            epsilon = EPSILON_FOUR_SIG_DIGITS
-        );
-        assert_eq!(c.additional_unhooks, 3)
+        assert_eq!(c.personal, percent(6));
+        assert_eq!(c.global, percent(3));
+        assert_eq!(c.up_the_ante_coeff.unwrap(), percent(2));
+        assert_eq!(c.additional_unhooks, 3);
+        assert!(c.conditional_lock)
+    }
+
+    #[test]
+    fn luck_lock_requires_condition() {
+        let record =
+            &LoadoutLuckRecord::with_personal(percent(2)) + &LoadoutLuckRecord::with_luck_lock();
+        let lock_luck = |condition_met: bool| -> Luck {
+            let player = LoadoutPlayerConverter::new(true, condition_met).convert(record);
+            let team = &TeamLuckRecord::with_global(misc::BASE_UNHOOK_CHANCE)
+                + &PlayerTeamConverter::new(LivingCount::default()).convert(&player);
+            let (one_try, _) = team
+                .make_single_and_total_unhook_pairs(misc::BASE_UNHOOK_ATTEMPTS)
+                .next()
+                .expect("team of one player has one luck pair");
+            one_try
+        };
+
+        assert_eq!(lock_luck(false), percent(6));
+        assert_eq!(lock_luck(true), misc::LOCKED_LUCK)
     }
 
     #[test]
     fn best_case_integration() {
         let mut personals = ArrayVec::new();
-        personals.push((0.04, 3)); // slippery meat
+        personals.push((percent(4), 3)); // slippery meat
         let player = TeamLuckRecord {
-            global: 0.03 + 0.03 * 3., // salty lips & up the ante with 3 others living
+            global: percent(3) + percent(3) * 3, // salty lips & up the ante with 3 others living
             personals: Some(personals),
+            ..TeamLuckRecord::default()
         };
         let full_team = &altruistic_team() + &player;
-        let full_luck: Vec<(Luck, Luck)> = full_team.make_single_and_total_unhook_pairs().collect();
+        let full_luck: Vec<(Luck, Probability)> = full_team
+            .make_single_and_total_unhook_pairs(misc::BASE_UNHOOK_ATTEMPTS)
+            .collect();
         let (one_try, all_tries) = full_luck.get(3).expect("3 less than full team size");
-        assert_approx_eq!(
-            f64,
+        // 1 - (1 - 56%)^6
+        let expected_all_tries = Probability::one() - exact_probability(Ratio::new(11, 25)).pow(6);
+        assert_eq!(*all_tries, expected_all_tries);
+        assert_eq!(
             *all_tries,
-            0.992743686,
-            epsilon = EPSILON_FOUR_SIG_DIGITS
+            BigRational::new(242_369_064.into(), 244_140_625.into())
         );
-        assert_approx_eq!(f64, *one_try, 0.5600, epsilon = EPSILON_FOUR_SIG_DIGITS)
+        assert_eq!(*one_try, percent(56))
     }
 }
@@ -0,0 +1,169 @@
+use derive_getters::Getters;
+use num_traits::Zero;
+
+use super::{
+    luck_record::Probability,
+    offering::{Offering, OfferingSlot},
+    perk::{PerkName, Tier, TierSlot},
+    ruleset::Ruleset,
+    team::Team,
+    update::{SurvivorId, SurvivorUpdate},
+};
+
+/// The luck perks a trial edit may equip or re-tier
+const LUCK_PERKS: [PerkName; 4] = [
+    PerkName::SlipperyMeat,
+    PerkName::UpTheAnte,
+    PerkName::Deliverance,
+    PerkName::Wicked,
+];
+
+/// How much one change to one survivor would move the odds of the team
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct MarginalValue {
+    update: SurvivorUpdate,
+    /// The change in each survivor's total chance, by survivor id
+    total_changes: Vec<Probability>,
+    /// The change in the average total chance of the living survivors
+    average_change: Probability,
+}
+
+impl Team {
+    /// Tries every luck perk, tier and offering on every living survivor,
+    /// giving the worth of each change which the team would accept, best
+    /// first. The team itself is left as it is.
+    pub fn marginal_values(&self, rules: &Ruleset) -> Vec<MarginalValue> {
+        let before = self.exact_luck_output(rules);
+        let before_average = self.living_average(rules);
+
+        let mut values: Vec<MarginalValue> = self
+            .trial_updates()
+            .filter_map(|update| {
+                let mut trial = *self;
+                trial.alter(update).ok()?;
+                let total_changes = trial
+                    .exact_luck_output(rules)
+                    .into_iter()
+                    .zip(&before)
+                    .map(|((_, after), (_, before))| after - before)
+                    .collect();
+                Some(MarginalValue {
+                    update,
+                    total_changes,
+                    average_change: trial.living_average(rules) - &before_average,
+                })
+            })
+            .collect();
+        // A stable sort keeps trial order among equally good changes
+        values.sort_by(|a, b| b.average_change.cmp(&a.average_change));
+        values
+    }
+
+    /// Every change of one luck item on one living survivor which
+    /// differs from what they already have.
+    fn trial_updates(&self) -> impl Iterator<Item = SurvivorUpdate> + '_ {
+        self.list()
+            .enumerate()
+            .filter(|(_, player)| player.is_alive())
+            .flat_map(move |(i, player)| {
+                let id = SurvivorId::try_new(i).expect("Team list is within team capacity.");
+                let perks = LUCK_PERKS
+                    .into_iter()
+                    .flat_map(|perk| Tier::iterator().map(move |tier| (perk, tier)))
+                    .filter(|(perk, tier)| player.get_perk_tier(*perk) != Some(tier))
+                    .map(move |(perk, tier)| {
+                        SurvivorUpdate::perk()
+                            .id(id)
+                            .perk(perk)
+                            .tier(TierSlot::new(Some(tier)))
+                            .call()
+                    });
+                let offerings = Offering::iterator()
+                    .filter(|x| **player.offering() != Some(*x))
+                    .map(move |x| {
+                        SurvivorUpdate::offering()
+                            .id(id)
+                            .offering(OfferingSlot::new(Some(x)))
+                            .call()
+                    });
+                perks.chain(offerings).collect::<Vec<_>>()
+            })
+    }
+
+    fn living_average(&self, rules: &Ruleset) -> Probability {
+        let living: Vec<Probability> = self
+            .exact_luck_output(rules)
+            .into_iter()
+            .zip(self.list())
+            .filter(|(_, player)| player.is_alive())
+            .map(|((_, total), _)| total)
+            .collect();
+        match living.len() {
+            0 => Probability::zero(),
+            n => living.into_iter().sum::<Probability>() / Probability::from_integer(n.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn id(x: usize) -> SurvivorId {
+        SurvivorId::try_new(x).expect("Test ids are in team capacity.")
+    }
+
+    proptest! {
+        #[test]
+        fn values_ranked_best_first(team in crate::team::arb::team()) {
+            let values = team.marginal_values(&Ruleset::default());
+
+            for pair in values.windows(2) {
+                prop_assert!(pair[0].average_change() >= pair[1].average_change());
+            }
+            for value in &values {
+                prop_assert_eq!(value.total_changes().len(), *team.size());
+                prop_assert!(team.get_player(*value.update().id()).is_alive());
+            }
+        }
+    }
+
+    #[test]
+    fn empty_team_best_upgrade_is_global() {
+        let values = Team::default().marginal_values(&Ruleset::default());
+        let best = values.first().expect("An empty team may equip anything.");
+
+        // Up the Ante at tier 3 gives everyone 9%, more than any other single item
+        assert_eq!(
+            *best.update(),
+            SurvivorUpdate::perk()
+                .id(id(0))
+                .perk(PerkName::UpTheAnte)
+                .tier(TierSlot::new(Some(Tier::Three)))
+                .call()
+        );
+        assert!(
+            best.total_changes()
+                .iter()
+                .all(|x| *x > Probability::zero())
+        )
+    }
+
+    #[test]
+    fn personal_offering_only_moves_its_owner() {
+        let values = Team::default().marginal_values(&Ruleset::default());
+        let chalk = SurvivorUpdate::offering()
+            .id(id(1))
+            .offering(OfferingSlot::new(Some(Offering::ChalkPouch)))
+            .call();
+        let value = values
+            .iter()
+            .find(|x| *x.update() == chalk)
+            .expect("Chalk pouch is a trial edit of an empty team.");
+
+        for (i, change) in value.total_changes().iter().enumerate() {
+            assert_eq!(i == 1, *change > Probability::zero());
+        }
+    }
+}
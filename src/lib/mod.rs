@@ -1,10 +1,38 @@
+#[cfg(feature = "capi")]
+#[allow(unsafe_code)]
+pub mod capi;
+pub mod code;
 pub mod constants;
+pub mod distribution;
+pub mod history;
+pub mod marginal;
 pub mod offering;
+pub mod optimizer;
+pub mod patch;
 pub mod perk;
+pub mod provenance;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod roster;
+pub mod ruleset;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "session-log")]
+pub mod session_log;
+pub mod simulation;
 pub mod team;
+pub mod timeline;
 pub mod update;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 mod living_count;
 mod loadout;
 mod luck_record;
 mod player;
+#[cfg(feature = "serde")]
+mod team_form;
+#[cfg(feature = "serde")]
+mod update_form;
+
+pub use luck_record::{Luck, Probability};
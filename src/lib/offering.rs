@@ -1,11 +1,21 @@
 use nutype::nutype;
 use strum::{EnumIter, IntoEnumIterator};
 
-use crate::constants::offering_luck as k;
-
-use super::luck_record::{LoadoutLuckRecord, Luck};
+use super::{
+    luck_record::{LoadoutLuckRecord, Luck},
+    ruleset::Ruleset,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(eq, eq_int, frozen, hash, module = "hook_escape_calculator")
+)]
 pub enum Offering {
     ChalkPouch,
     CreamPouch,
@@ -20,22 +30,20 @@ pub enum Offering {
 )]
 pub struct OfferingSlot(Option<Offering>);
 
-impl From<&Offering> for LoadoutLuckRecord {
-    fn from(offering: &Offering) -> Self {
-        if offering.luck_is_personal() {
-            LoadoutLuckRecord::with_personal(offering.luck_value())
+impl Offering {
+    pub fn luck_record(&self, rules: &Ruleset) -> LoadoutLuckRecord {
+        if self.luck_is_personal() {
+            LoadoutLuckRecord::with_personal(self.luck_value(rules))
         } else {
-            LoadoutLuckRecord::with_global(offering.luck_value())
+            LoadoutLuckRecord::with_global(self.luck_value(rules))
         }
     }
-}
-
-impl Offering {
-    const fn luck_value(&self) -> Luck {
+    fn luck_value(&self, rules: &Ruleset) -> Luck {
+        let values = rules.offering();
         match self {
-            Offering::ChalkPouch | Offering::SaltPouch => k::SLIGHT_LUCK,
-            Offering::CreamPouch | Offering::SaltStatuette => k::MODERATE_LUCK,
-            Offering::IvoryPouch | Offering::SaltyLips => k::GREAT_LUCK,
+            Offering::ChalkPouch | Offering::SaltPouch => *values.slight(),
+            Offering::CreamPouch | Offering::SaltStatuette => *values.moderate(),
+            Offering::IvoryPouch | Offering::SaltyLips => *values.great(),
         }
     }
     const fn luck_is_personal(&self) -> bool {
@@ -0,0 +1,618 @@
+use bon::Builder;
+use itertools::Itertools;
+use num_traits::Zero;
+
+use super::{
+    constants::misc as k,
+    luck_record::{LoadoutLuckRecord, Luck, Probability},
+    offering::{Offering, OfferingSlot},
+    perk::{Perk, PerkName, Tier, TierSlot},
+    player::Player,
+    ruleset::Ruleset,
+    team::Team,
+    update::{
+        LoadoutUpdate, PerkUpdate, SurvivorId, SurvivorUpdate, SurvivorUpdateData, UpdateError,
+    },
+};
+
+/// What the optimizer tries to make as large as possible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// The average total chance of the living survivors
+    Average,
+    /// The total chance of the living survivor with the worst odds
+    Minimum,
+    /// The total chance of one survivor
+    Survivor(SurvivorId),
+}
+
+/// The luck items a survivor may bring into the match
+#[derive(Debug, Clone, PartialEq, Eq, Default, Builder)]
+pub struct SurvivorConstraints {
+    /// Every perk and tier the survivor has unlocked
+    #[builder(default)]
+    owned_perks: Vec<Perk>,
+    #[builder(default)]
+    owned_offerings: Vec<Offering>,
+    /// Perk slots the survivor keeps for perks of their own choosing
+    #[builder(default)]
+    kept_free_slots: usize,
+}
+
+impl SurvivorConstraints {
+    /// A survivor who owns every luck perk at every tier and every offering
+    pub fn owns_everything() -> Self {
+        let names = [
+            PerkName::SlipperyMeat,
+            PerkName::UpTheAnte,
+            PerkName::Deliverance,
+            PerkName::Wicked,
+        ];
+        Self {
+            owned_perks: names
+                .into_iter()
+                .cartesian_product(Tier::iterator())
+                .map(|(name, tier)| Perk::new(name, tier))
+                .collect(),
+            owned_offerings: Offering::iterator().collect(),
+            kept_free_slots: 0,
+        }
+    }
+
+    /// The best tier of a perk the survivor owns
+    pub fn best_tier(&self, name: PerkName) -> Option<Tier> {
+        self.owned_perks
+            .iter()
+            .filter(|perk| *perk.name() == name)
+            .map(|perk| *perk.tier())
+            .max()
+    }
+    /// Owning a perk at one tier replaces whichever tiers of it were owned,
+    /// as the game only ever lets a survivor bring their best tier
+    pub fn set_owned_tier(&mut self, name: PerkName, tier: Option<Tier>) {
+        self.owned_perks.retain(|perk| *perk.name() != name);
+        self.owned_perks
+            .extend(tier.map(|tier| Perk::new(name, tier)));
+    }
+    pub fn owns_offering(&self, offering: Offering) -> bool {
+        self.owned_offerings.contains(&offering)
+    }
+    pub fn set_owns_offering(&mut self, offering: Offering, owned: bool) {
+        self.owned_offerings.retain(|x| *x != offering);
+        if owned {
+            self.owned_offerings.push(offering);
+        }
+    }
+    pub const fn kept_free_slots(&self) -> usize {
+        self.kept_free_slots
+    }
+    /// Keeps at most every perk slot free
+    pub fn set_kept_free_slots(&mut self, slots: usize) {
+        self.kept_free_slots = slots.min(k::MAX_PERKS);
+    }
+
+    /// Every combination of owned luck perks and offering this survivor
+    /// could bring, with at most one tier of each perk.
+    fn loadouts(&self) -> Vec<(Vec<Perk>, OfferingSlot)> {
+        let perk_groups = self
+            .owned_perks
+            .iter()
+            .filter(|perk| perk.name().is_luck_perk())
+            .into_group_map_by(|perk| *perk.name());
+        let perk_choices: Vec<Vec<Perk>> = perk_groups
+            .into_values()
+            .map(|tiers| {
+                std::iter::once(None)
+                    .chain(tiers.into_iter().copied().map(Some))
+                    .collect::<Vec<_>>()
+            })
+            .multi_cartesian_product()
+            .map(|perks| perks.into_iter().flatten().collect())
+            .collect();
+        // Owning no luck perks still leaves the choice of bringing none
+        let perk_choices = match perk_choices.is_empty() {
+            true => vec![Vec::new()],
+            false => perk_choices,
+        };
+        let offering_choices = std::iter::once(None)
+            .chain(self.owned_offerings.iter().copied().map(Some))
+            .map(OfferingSlot::new);
+
+        perk_choices
+            .into_iter()
+            .sorted_by_key(Vec::len)
+            .cartesian_product(offering_choices.collect_vec())
+            .collect()
+    }
+}
+
+/// Which items each survivor may bring, by survivor id
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Constraints([SurvivorConstraints; k::TEAM_MAX_CAPACITY]);
+
+impl Constraints {
+    pub fn owns_everything() -> Self {
+        Self(std::array::from_fn(|_| {
+            SurvivorConstraints::owns_everything()
+        }))
+    }
+    pub fn set(&mut self, id: SurvivorId, constraints: SurvivorConstraints) {
+        self.0[*id] = constraints;
+    }
+    pub fn get(&self, id: SurvivorId) -> &SurvivorConstraints {
+        &self.0[*id]
+    }
+    pub fn get_mut(&mut self, id: SurvivorId) -> &mut SurvivorConstraints {
+        &mut self.0[*id]
+    }
+}
+
+impl Team {
+    /// Searches every assignment of owned luck perks and offerings to the
+    /// survivors for the team which best meets the objective. Living status,
+    /// lock conditions and perks which do not affect luck are kept, while
+    /// the luck perks and offerings already equipped are replaced.
+    ///
+    /// Of the equally good assignments, the first found is returned, which
+    /// favours survivors bringing fewer perks. An objective naming a
+    /// survivor who is not in the team is refused.
+    ///
+    /// Survivors who are alike and own the same items are searched as a
+    /// group, so that eight survivors owning everything take a few
+    /// thousand candidates rather than every one of millions.
+    pub fn optimize(
+        &self,
+        rules: &Ruleset,
+        constraints: &Constraints,
+        objective: Objective,
+    ) -> Result<Team, UpdateError> {
+        if let Objective::Survivor(id) = objective
+            && !self.contains(id)
+        {
+            return Err(UpdateError::NotInTeam(id));
+        }
+        let cleared = self.without_luck_items();
+        let groups = interchangeable_groups(&cleared, constraints, objective);
+        let choices = groups.iter().map(|ids| {
+            let id = ids[0];
+            undominated_players(cleared.get_player(id), constraints.get(id), rules)
+                .into_iter()
+                .combinations_with_replacement(ids.len())
+                .collect_vec()
+        });
+
+        let best = choices
+            .multi_cartesian_product()
+            .map(|loadouts| {
+                let mut team = cleared;
+                let assigned = groups.iter().flatten().zip(loadouts.into_iter().flatten());
+                for (id, updates) in assigned {
+                    for update in updates {
+                        team.alter(SurvivorUpdate::data().id(*id).update(update).call())
+                            .expect("Candidates were already applied to this survivor.");
+                    }
+                }
+                team
+            })
+            .map(|team| (score(&team, rules, objective), team))
+            .reduce(|best, next| if next.0 > best.0 { next } else { best })
+            .map(|(_, team)| team)
+            .expect("Every survivor may bring nothing, so there is always a candidate.");
+        Ok(best)
+    }
+
+    fn without_luck_items(&self) -> Team {
+        let mut team = *self;
+        for (i, player) in self.list().enumerate() {
+            let id = SurvivorId::try_new(i).expect("Team list is within team capacity.");
+            let names = player.equipped_perk_names().filter(PerkName::is_luck_perk);
+            let removals = names
+                .map(|name| LoadoutUpdate::Perk(PerkUpdate::new(name, TierSlot::new(None))))
+                .chain(std::iter::once(LoadoutUpdate::Offering(OfferingSlot::new(
+                    None,
+                ))));
+            for update in removals.collect_vec() {
+                team.alter(
+                    SurvivorUpdate::data()
+                        .id(id)
+                        .update(SurvivorUpdateData::LoadoutUpdate(update))
+                        .call(),
+                )
+                .expect("Removing items is never refused.");
+            }
+        }
+        team
+    }
+}
+
+/// The survivors of the team in groups of those who are alike and own the
+/// same items. Swapping the loadouts of two survivors of a group changes
+/// nothing the objective can see, so only which loadouts a group brings
+/// between them is searched. The survivor an objective names is alike to
+/// no one.
+fn interchangeable_groups(
+    team: &Team,
+    constraints: &Constraints,
+    objective: Objective,
+) -> Vec<Vec<SurvivorId>> {
+    let named = |id| objective == Objective::Survivor(id);
+    let mut groups: Vec<Vec<SurvivorId>> = Vec::new();
+    for id in team.ids() {
+        let alike = |other: SurvivorId| {
+            !named(id)
+                && !named(other)
+                && team.get_player(id) == team.get_player(other)
+                && constraints.get(id) == constraints.get(other)
+        };
+        match groups.iter_mut().find(|group| alike(group[0])) {
+            Some(group) => group.push(id),
+            None => groups.push(vec![id]),
+        }
+    }
+    groups
+}
+
+/// The updates giving each loadout a survivor could bring, leaving out
+/// those which give no more luck than another in any respect.
+fn undominated_players(
+    player: &Player,
+    constraints: &SurvivorConstraints,
+    rules: &Ruleset,
+) -> Vec<Vec<SurvivorUpdateData>> {
+    let candidates = constraints
+        .loadouts()
+        .into_iter()
+        .filter_map(|(perks, offering)| {
+            let updates: Vec<SurvivorUpdateData> = perks
+                .into_iter()
+                .map(|perk| {
+                    LoadoutUpdate::Perk(PerkUpdate::new(
+                        *perk.name(),
+                        TierSlot::new(Some(*perk.tier())),
+                    ))
+                })
+                .chain(std::iter::once(LoadoutUpdate::Offering(offering)))
+                .map(SurvivorUpdateData::LoadoutUpdate)
+                .collect();
+
+            let mut candidate = *player;
+            for update in &updates {
+                candidate.alter(*update).ok()?;
+            }
+            (candidate.free_perk_slots() >= constraints.kept_free_slots)
+                .then(|| (candidate.make_player_luck(rules).0, updates))
+        });
+
+    let lock_met = player.lock_condition_met();
+    let mut kept: Vec<(LoadoutLuckRecord, Vec<SurvivorUpdateData>)> = Vec::new();
+    for (record, updates) in candidates {
+        if kept.iter().all(|(x, _)| !dominates(x, &record, lock_met)) {
+            kept.retain(|(x, _)| !dominates(&record, x, lock_met));
+            kept.push((record, updates));
+        }
+    }
+    kept.into_iter().map(|(_, updates)| updates).collect()
+}
+
+/// Whether one record gives its survivor and the rest of the team at least
+/// as much luck as another, in which case no survivor's odds are better
+/// with the other. Global luck counts towards the survivor's own luck as
+/// well as everyone else's. A lock only counts when its condition is met,
+/// as until then it gives nothing.
+fn dominates(a: &LoadoutLuckRecord, b: &LoadoutLuckRecord, lock_met: bool) -> bool {
+    let uta = |x: &LoadoutLuckRecord| x.up_the_ante_coeff().unwrap_or_else(Luck::zero);
+    let own = |x: &LoadoutLuckRecord| x.personal() + x.global();
+    own(a) >= own(b)
+        && a.global() >= b.global()
+        && uta(a) >= uta(b)
+        && a.additional_unhooks() >= b.additional_unhooks()
+        && (!lock_met || a.conditional_lock() >= b.conditional_lock())
+}
+
+fn score(team: &Team, rules: &Ruleset, objective: Objective) -> Probability {
+    let totals = team
+        .exact_luck_output(rules)
+        .into_iter()
+        .map(|(_, total)| total);
+    let living = || {
+        totals
+            .clone()
+            .zip(team.list())
+            .filter(|(_, player)| player.is_alive())
+            .map(|(total, _)| total)
+    };
+
+    match objective {
+        // Every candidate has the same living survivors, so their sum
+        // ranks candidates just as their average would.
+        Objective::Average => living().sum(),
+        Objective::Minimum => living().min().unwrap_or_else(Probability::zero),
+        Objective::Survivor(id) => totals
+            .clone()
+            .nth(*id)
+            .expect("Only survivors in the team are optimized for."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perk;
+    use proptest::prelude::*;
+
+    fn id(x: usize) -> SurvivorId {
+        SurvivorId::try_new(x).expect("Test ids are in team capacity.")
+    }
+
+    prop_compose! {
+        /// Owning at most two offerings keeps every assignment few enough to try
+        fn survivor_constraints()(
+            tiers in prop::collection::vec(prop::option::of(perk::arb::tier()), 4),
+            owned_offerings in prop::sample::subsequence(Offering::iterator().collect_vec(), 0..=2),
+            kept_free_slots in 0..=k::MAX_PERKS,
+        ) -> SurvivorConstraints {
+            let mut owned = SurvivorConstraints::builder()
+                .owned_offerings(owned_offerings)
+                .kept_free_slots(kept_free_slots)
+                .build();
+            let names = PerkName::iterator().filter(PerkName::is_luck_perk);
+            for (name, tier) in names.zip(tiers) {
+                owned.set_owned_tier(name, tier);
+            }
+            owned
+        }
+    }
+
+    /// The best score of every assignment of undominated loadouts, tried
+    /// one survivor at a time
+    fn best_score_of_every_assignment(
+        team: &Team,
+        rules: &Ruleset,
+        constraints: &Constraints,
+        objective: Objective,
+    ) -> Probability {
+        let cleared = team.without_luck_items();
+        cleared
+            .ids()
+            .map(|id| undominated_players(cleared.get_player(id), constraints.get(id), rules))
+            .multi_cartesian_product()
+            .map(|players| {
+                let mut team = cleared;
+                for (id, updates) in cleared.ids().zip(players) {
+                    for update in updates {
+                        team.alter(SurvivorUpdate::data().id(id).update(update).call())
+                            .expect("Candidates were already applied to this survivor.");
+                    }
+                }
+                score(&team, rules, objective)
+            })
+            .max()
+            .expect("Every survivor may bring nothing, so there is always a candidate.")
+    }
+
+    proptest! {
+        #[test]
+        fn grouped_search_finds_the_best_assignment(
+            states in prop::collection::vec((any::<bool>(), any::<bool>()), 2),
+            owned in prop::collection::vec(survivor_constraints(), 2),
+            picks in prop::collection::vec((0..2usize, 0..2usize), 1..=4),
+            objective in prop_oneof![
+                Just(Objective::Average),
+                Just(Objective::Minimum),
+                (0..4usize).prop_map(|x| Objective::Survivor(id(x))),
+            ]
+        ) {
+            let size = crate::team::TeamSize::try_new(picks.len()).expect("Picks fit in a team.");
+            let mut team = Team::with_size(size);
+            let mut constraints = Constraints::default();
+            // Survivors drawn from the same state and ownership are alike
+            for (i, (state, ownership)) in picks.into_iter().enumerate() {
+                let (alive, lock_met) = states[state];
+                team.alter(SurvivorUpdate::living_status().id(id(i)).alive(alive).call())
+                    .expect("Survivors picked are in the team.");
+                team.alter(SurvivorUpdate::lock_condition().id(id(i)).met(lock_met).call())
+                    .expect("Survivors picked are in the team.");
+                constraints.set(id(i), owned[ownership].clone());
+            }
+            let rules = Ruleset::default();
+
+            match team.optimize(&rules, &constraints, objective) {
+                Ok(best) => prop_assert_eq!(
+                    score(&best, &rules, objective),
+                    best_score_of_every_assignment(&team, &rules, &constraints, objective)
+                ),
+                Err(e) => prop_assert_eq!(e, UpdateError::NotInTeam(match objective {
+                    Objective::Survivor(x) => x,
+                    _ => unreachable!("Only a named survivor may be outside the team."),
+                })),
+            }
+        }
+    }
+
+    #[test]
+    fn unmet_lock_not_suggested() {
+        let best = Team::default()
+            .optimize(
+                &Ruleset::default(),
+                &Constraints::owns_everything(),
+                Objective::Average,
+            )
+            .expect("Objectives in the tests name survivors in the team.");
+
+        assert!(
+            best.list()
+                .flat_map(Player::equipped_perk_names)
+                .all(|x| !x.is_conditional_lock())
+        )
+    }
+
+    #[test]
+    fn met_lock_takes_the_last_slot() {
+        let mut team = Team::default();
+        team.alter(SurvivorUpdate::lock_condition().id(id(0)).met(true).call())
+            .expect("Survivor 0 is in the team.");
+        let mut constraints = Constraints::owns_everything();
+        constraints
+            .get_mut(id(0))
+            .set_kept_free_slots(k::MAX_PERKS - 1);
+
+        let best = team
+            .optimize(
+                &Ruleset::default(),
+                &constraints,
+                Objective::Survivor(id(0)),
+            )
+            .expect("Objectives in the tests name survivors in the team.");
+
+        assert!(
+            best.get_player(id(0))
+                .equipped_perk_names()
+                .any(|x| x.is_conditional_lock())
+        )
+    }
+
+    #[test]
+    fn owning_nothing_brings_nothing() {
+        let rules = Ruleset::default();
+        let team = Team::default();
+
+        assert_eq!(
+            team.optimize(&rules, &Constraints::default(), Objective::Average)
+                .expect("Objectives in the tests name survivors in the team."),
+            team
+        )
+    }
+
+    #[test]
+    fn single_up_the_ante_goes_to_a_living_survivor() {
+        let rules = Ruleset::default();
+        let mut team = Team::default();
+        team.alter(
+            SurvivorUpdate::living_status()
+                .id(id(0))
+                .alive(false)
+                .call(),
+        )
+        .expect("Changing living status is never refused.");
+        let uta = SurvivorConstraints::builder()
+            .owned_perks(vec![Perk::new(PerkName::UpTheAnte, Tier::Three)])
+            .build();
+        let mut constraints = Constraints::default();
+        constraints.set(id(0), uta.clone());
+        constraints.set(id(1), uta);
+
+        let best = team
+            .optimize(&rules, &constraints, Objective::Average)
+            .expect("Objectives in the tests name survivors in the team.");
+
+        assert_eq!(
+            best.get_player(id(0)).get_perk_tier(PerkName::UpTheAnte),
+            None
+        );
+        assert_eq!(
+            best.get_player(id(1)).get_perk_tier(PerkName::UpTheAnte),
+            Some(&Tier::Three)
+        )
+    }
+
+    #[test]
+    fn named_survivor_takes_personal_offering() {
+        let rules = Ruleset::default();
+        let mut constraints = Constraints::default();
+        constraints.set(
+            id(2),
+            SurvivorConstraints::builder()
+                .owned_offerings(vec![Offering::SaltyLips, Offering::IvoryPouch])
+                .build(),
+        );
+
+        // Both give the same luck to survivor 2, but only salty lips helps the rest
+        let for_self = Team::default()
+            .optimize(&rules, &constraints, Objective::Survivor(id(2)))
+            .expect("Objectives in the tests name survivors in the team.");
+        let for_team = Team::default()
+            .optimize(&rules, &constraints, Objective::Average)
+            .expect("Objectives in the tests name survivors in the team.");
+
+        assert_eq!(
+            score(&for_self, &rules, Objective::Survivor(id(2))),
+            score(&for_team, &rules, Objective::Survivor(id(2)))
+        );
+        assert_eq!(
+            *for_team.get_player(id(2)).offering(),
+            OfferingSlot::new(Some(Offering::SaltyLips))
+        )
+    }
+
+    #[test]
+    fn kept_free_slots_limit_perks() {
+        let rules = Ruleset::default();
+        let mut constraints = Constraints::owns_everything();
+        constraints.set(
+            id(0),
+            SurvivorConstraints {
+                kept_free_slots: k::MAX_PERKS - 1,
+                ..SurvivorConstraints::owns_everything()
+            },
+        );
+
+        let best = Team::default()
+            .optimize(&rules, &constraints, Objective::Survivor(id(0)))
+            .expect("Objectives in the tests name survivors in the team.");
+        let player = best.get_player(id(0));
+
+        assert_eq!(player.free_perk_slots(), k::MAX_PERKS - 1)
+    }
+
+    #[test]
+    fn owning_a_tier_replaces_other_tiers() {
+        let mut owned = SurvivorConstraints::owns_everything();
+        owned.set_owned_tier(PerkName::UpTheAnte, Some(Tier::Two));
+        owned.set_owned_tier(PerkName::Wicked, None);
+
+        assert_eq!(owned.best_tier(PerkName::UpTheAnte), Some(Tier::Two));
+        assert_eq!(owned.best_tier(PerkName::Wicked), None);
+        assert_eq!(owned.best_tier(PerkName::SlipperyMeat), Some(Tier::Three));
+        assert!(
+            owned
+                .loadouts()
+                .iter()
+                .flat_map(|(perks, _)| perks)
+                .all(|perk| *perk != Perk::new(PerkName::UpTheAnte, Tier::Three))
+        )
+    }
+
+    #[test]
+    fn survivor_outside_team_refused() {
+        let size = crate::team::TeamSize::try_new(2).expect("Two is a team size.");
+        let team = Team::with_size(size);
+
+        assert_eq!(
+            team.optimize(
+                &Ruleset::default(),
+                &Constraints::owns_everything(),
+                Objective::Survivor(id(5))
+            ),
+            Err(UpdateError::NotInTeam(id(5)))
+        )
+    }
+
+    #[test]
+    fn optimum_beats_current_team() {
+        let rules = Ruleset::default();
+        let mut team = Team::default();
+        team.alter(
+            SurvivorUpdate::offering()
+                .id(id(3))
+                .offering(OfferingSlot::new(Some(Offering::ChalkPouch)))
+                .call(),
+        )
+        .expect("Offerings are never refused.");
+
+        let best = team
+            .optimize(&rules, &Constraints::owns_everything(), Objective::Minimum)
+            .expect("Objectives in the tests name survivors in the team.");
+
+        assert!(score(&best, &rules, Objective::Minimum) > score(&team, &rules, Objective::Minimum))
+    }
+}
@@ -0,0 +1,141 @@
+use derive_more::Display;
+
+use crate::constants::percent;
+
+use super::ruleset::{LockPerks, Ruleset, TierValues};
+
+/// A release of the game, such as `6.1.0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
+#[display("{major}.{minor}.{patch}")]
+pub struct GameVersion {
+    major: u8,
+    minor: u8,
+    patch: u8,
+}
+
+impl GameVersion {
+    pub const fn new(major: u8, minor: u8, patch: u8) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl std::str::FromStr for GameVersion {
+    type Err = PatchError;
+
+    /// Reads versions such as `6.1.0`, or `6.1` for the first release of a minor version
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PatchError::InvalidVersion(s.to_owned());
+        let mut parts = s
+            .trim()
+            .split('.')
+            .map(|x| x.parse::<u8>().map_err(|_| invalid()));
+
+        let major = parts.next().ok_or_else(invalid)??;
+        let minor = parts.next().ok_or_else(invalid)??;
+        let patch = parts.next().transpose()?.unwrap_or(0);
+        match parts.next() {
+            Some(_) => Err(invalid()),
+            None => Ok(Self::new(major, minor, patch)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum PatchError {
+    #[display("{_0} is not a game version, which are written like 6.1.0")]
+    InvalidVersion(String),
+    #[display("{_0} is older than every known game version")]
+    UnknownVersion(GameVersion),
+}
+
+impl std::error::Error for PatchError {}
+
+/// Every game version which changed a balance value that luck depends on,
+/// oldest first, paired with the ruleset that it put into force. A version
+/// which is not listed uses the ruleset of the latest version before it.
+///
+/// The base unhook chance of 4% and three attempts, the 1%, 2% and 3% of
+/// the chalk and salt offerings, and Slippery Meat's 2%, 3% and 4% with
+/// three more attempts have held since release. The versions listed are
+/// those whose chapter brought a new luck perk into the game.
+const HISTORY: [(GameVersion, Ruleset); 4] = [
+    // Release, with Slippery Meat as the only luck perk
+    (
+        GameVersion::new(1, 0, 0),
+        Ruleset::const_default()
+            .with_up_the_ante(TierValues::new(percent(0), percent(0), percent(0)))
+            .with_lock_perks(LockPerks::new(false, false)),
+    ),
+    // Of Flesh and Mud, which added Ace Visconti and his Up the Ante
+    (
+        GameVersion::new(1, 4, 0),
+        Ruleset::const_default().with_lock_perks(LockPerks::new(false, false)),
+    ),
+    // Shattered Bloodline, which added Adam Francis and his Deliverance
+    (
+        GameVersion::new(2, 2, 0),
+        Ruleset::const_default().with_lock_perks(LockPerks::new(true, false)),
+    ),
+    // All Things Wicked, which added Sable Ward and her Wicked
+    (GameVersion::new(7, 2, 0), Ruleset::const_default()),
+];
+
+/// The game versions that changed a luck balance value, oldest first
+pub fn versions() -> impl DoubleEndedIterator<Item = GameVersion> {
+    HISTORY.iter().map(|(version, _)| *version)
+}
+
+/// The ruleset which was in force during a game version
+pub fn ruleset_for(version: GameVersion) -> Result<Ruleset, PatchError> {
+    HISTORY
+        .iter()
+        .rev()
+        .find(|(released, _)| *released <= version)
+        .map(|(_, rules)| *rules)
+        .ok_or(PatchError::UnknownVersion(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_is_ordered() {
+        let versions: Vec<_> = versions().collect();
+        assert!(versions.is_sorted_by(|a, b| a < b))
+    }
+
+    #[test]
+    fn every_ruleset_is_valid() {
+        assert!(HISTORY.iter().all(|(_, rules)| rules.validate().is_ok()))
+    }
+
+    #[test]
+    fn latest_patch_is_live_ruleset() {
+        let latest = versions().next_back().expect("History is not empty.");
+        assert_eq!(ruleset_for(latest), Ok(Ruleset::default()))
+    }
+
+    #[test]
+    fn unlisted_version_uses_previous_patch() {
+        let version: GameVersion = "1.3".parse().expect("1.3 is a version.");
+        assert_eq!(ruleset_for(version), ruleset_for(GameVersion::new(1, 0, 0)))
+    }
+
+    #[test]
+    fn versions_parse() {
+        assert_eq!("6.1.0".parse(), Ok(GameVersion::new(6, 1, 0)));
+        assert_eq!("6.1".parse(), Ok(GameVersion::new(6, 1, 0)));
+        assert!("6".parse::<GameVersion>().is_err());
+        assert!("6.1.0.2".parse::<GameVersion>().is_err());
+        assert!("six".parse::<GameVersion>().is_err());
+        assert_eq!(
+            ruleset_for(GameVersion::new(0, 9, 0)),
+            Err(PatchError::UnknownVersion(GameVersion::new(0, 9, 0)))
+        );
+    }
+}
@@ -2,11 +2,12 @@ use derive_getters::Getters;
 use nutype::nutype;
 use strum::{EnumIter, IntoEnumIterator};
 
-use crate::constants::perk_luck as k;
+use crate::constants::misc::MAX_PERKS;
 
-use super::luck_record::LoadoutLuckRecord;
+use super::{luck_record::LoadoutLuckRecord, ruleset::Ruleset};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Getters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Perk {
     name: PerkName,
     tier: Tier,
@@ -19,6 +20,18 @@ impl Perk {
     pub const fn set_tier(&mut self, tier: Tier) {
         self.tier = tier
     }
+    pub fn luck_record(&self, rules: &Ruleset) -> LoadoutLuckRecord {
+        match self.name {
+            PerkName::UpTheAnte => LoadoutLuckRecord::with_uta(rules.up_the_ante().get(self.tier)),
+            PerkName::SlipperyMeat => slippery_meat_record(self.tier, rules),
+            PerkName::Deliverance | PerkName::Wicked if rules.lock_perks().locks(self.name) => {
+                LoadoutLuckRecord::with_luck_lock()
+            }
+            PerkName::Deliverance | PerkName::Wicked | PerkName::Other => {
+                LoadoutLuckRecord::default()
+            }
+        }
+    }
 }
 
 #[nutype(
@@ -30,7 +43,23 @@ pub struct PerkSlot(Option<Perk>);
 #[nutype(derive(Debug, Clone, Copy, PartialEq, Eq, Hash))]
 pub struct TierSlot(Option<Tier>);
 
+/// The position of a perk within a loadout
+#[nutype(
+    validate(less = MAX_PERKS),
+    derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, AsRef, Deref, Hash, Display)
+)]
+pub struct PerkSlotId(usize);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(eq, eq_int, frozen, hash, module = "hook_escape_calculator")
+)]
 pub enum Tier {
     One,
     Two,
@@ -57,37 +86,60 @@ impl std::fmt::Display for Tier {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[cfg_attr(test, derive(EnumIter))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(eq, eq_int, frozen, hash, module = "hook_escape_calculator")
+)]
 pub enum PerkName {
     SlipperyMeat,
     UpTheAnte,
+    Deliverance,
+    Wicked,
+    /// Any perk which has no effect on luck. Unlike the luck perks,
+    /// it may be equipped in more than one slot of a loadout.
+    Other,
 }
 
-impl From<&Perk> for LoadoutLuckRecord {
-    fn from(perk: &Perk) -> Self {
-        match perk.name {
-            PerkName::UpTheAnte => LoadoutLuckRecord::with_uta(uta_tier_percent(perk.tier)),
-            PerkName::SlipperyMeat => slippery_meat_record(perk.tier),
-        }
+impl std::fmt::Display for PerkName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PerkName::SlipperyMeat => "slippery meat",
+                PerkName::UpTheAnte => "up the ante",
+                PerkName::Deliverance => "deliverance",
+                PerkName::Wicked => "wicked",
+                PerkName::Other => "other",
+            }
+        )
     }
 }
 
-const fn uta_tier_percent(tier: Tier) -> f64 {
-    match tier {
-        Tier::One => k::UTA_TIER1,
-        Tier::Two => k::UTA_TIER2,
-        Tier::Three => k::UTA_TIER3,
+impl PerkName {
+    pub fn iterator() -> PerkNameIter {
+        Self::iter()
+    }
+    pub const fn is_luck_perk(&self) -> bool {
+        !matches!(self, PerkName::Other)
+    }
+    /// Whether the perk locks luck once its condition is met, which it
+    /// does the same at every tier
+    pub const fn is_conditional_lock(&self) -> bool {
+        matches!(self, PerkName::Deliverance | PerkName::Wicked)
     }
 }
 
-fn slippery_meat_record(tier: Tier) -> LoadoutLuckRecord {
-    let unhook_chance_record = LoadoutLuckRecord::with_personal(match tier {
-        Tier::One => k::SM_TIER1,
-        Tier::Two => k::SM_TIER2,
-        Tier::Three => k::SM_TIER3,
-    });
-    let unhook_count_record = LoadoutLuckRecord::with_unhook_mod(3);
+fn slippery_meat_record(tier: Tier, rules: &Ruleset) -> LoadoutLuckRecord {
+    let sm = rules.slippery_meat();
+    let unhook_chance_record = LoadoutLuckRecord::with_personal(sm.luck().get(tier));
+    let unhook_count_record = LoadoutLuckRecord::with_unhook_mod(sm.additional_unhooks());
     &unhook_chance_record + &unhook_count_record
 }
 
@@ -135,10 +187,13 @@ pub mod arb {
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[test]
-    fn perk_count_actual_and_expected_match() {
-        let perks: usize = PerkName::iter().count();
-        let expected = super::super::constants::misc::COUNT_LUCK_PERKS;
-        assert_eq!(perks, expected)
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn only_luck_perks_have_luck(perk in arb::perk()) {
+            let has_luck = perk.luck_record(&Ruleset::default()) != LoadoutLuckRecord::default();
+            prop_assert_eq!(has_luck, perk.name().is_luck_perk())
+        }
     }
 }
@@ -1,38 +1,48 @@
 use super::{
+    constants::misc as k,
     loadout::Loadout,
     luck_record::{LoadoutPlayerConverter, PlayerLuckRecord},
     offering::OfferingSlot,
-    perk::{PerkName, Tier},
-    update::SurvivorUpdateData as SUD,
+    perk::{PerkName, PerkSlot, PerkSlotId, Tier},
+    ruleset::Ruleset,
+    update::{SurvivorUpdateData as SUD, UpdateError},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Player {
     loadout: Loadout,
     is_alive: bool,
+    lock_condition_met: bool,
 }
 
 impl Player {
-    pub fn alter(&mut self, update: SUD) {
+    pub fn alter(&mut self, update: SUD) -> Result<(), UpdateError> {
         match update {
             SUD::Life(x) => self.is_alive = x,
-            SUD::LoadoutUpdate(x) => self.loadout.alter(x),
+            SUD::LockCondition(x) => self.lock_condition_met = x,
+            SUD::LoadoutUpdate(x) => self.loadout.alter(x)?,
         };
+        Ok(())
     }
 }
 
 // Delegated Getters
 impl Player {
     pub fn get_perk_tier(&self, name: PerkName) -> Option<&Tier> {
-        self.loadout
-            .get_perk(name)
-            .as_ref()
-            .as_ref()
-            .map(|perk| perk.tier())
+        self.loadout.get_perk(name).map(|perk| perk.tier())
+    }
+    pub fn perk_slot(&self, slot: PerkSlotId) -> &PerkSlot {
+        self.loadout.get_perk_slot(slot)
     }
     pub fn offering(&self) -> &OfferingSlot {
         self.loadout.offering()
     }
+    pub fn equipped_perk_names(&self) -> impl Iterator<Item = PerkName> + '_ {
+        self.loadout.equipped_perks().map(|perk| *perk.name())
+    }
+    pub fn free_perk_slots(&self) -> usize {
+        k::MAX_PERKS - self.loadout.equipped_perks().count()
+    }
 }
 
 // Getters
@@ -43,14 +53,24 @@ impl Player {
     pub const fn is_dead(&self) -> bool {
         !self.is_alive()
     }
+    /// Whether the in-game condition of Deliverance or Wicked
+    /// has been met, locking this player's luck if equipped.
+    pub const fn lock_condition_met(&self) -> bool {
+        self.lock_condition_met
+    }
     fn make_record_converter(&self) -> LoadoutPlayerConverter {
-        LoadoutPlayerConverter::new(self.is_alive)
+        LoadoutPlayerConverter::new(self.is_alive, self.lock_condition_met)
     }
 
     // Consider placing in sub-module
-    pub fn make_player_luck(&self) -> PlayerLuckRecord {
+    pub fn make_player_luck(&self, rules: &Ruleset) -> PlayerLuckRecord {
+        self.make_record_converter()
+            .convert(self.loadout.collate_luck(rules))
+    }
+    /// As `make_player_luck`, with the items behind the luck
+    pub fn make_player_luck_traced(&self, rules: &Ruleset) -> PlayerLuckRecord {
         self.make_record_converter()
-            .convert(self.loadout.collate_luck())
+            .convert(self.loadout.collate_luck_traced(rules))
     }
 }
 
@@ -59,6 +79,7 @@ impl Default for Player {
         Player {
             loadout: Loadout::default(),
             is_alive: true,
+            lock_condition_met: false,
         }
     }
 }
@@ -72,13 +93,15 @@ pub mod arb {
     prop_compose! {
         pub fn player()(
             loadout in loadout::arb::loadout(),
-            is_alive in 0..1
+            is_alive in 0..1,
+            lock_condition_met in any::<bool>()
         ) -> Player {
             let is_alive = is_alive != 0;
 
             Player {
                 loadout,
-                is_alive
+                is_alive,
+                lock_condition_met
             }
         }
     }
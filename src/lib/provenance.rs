@@ -0,0 +1,76 @@
+use derive_getters::Getters;
+use num_traits::Zero;
+
+use super::{
+    luck_record::Luck,
+    offering::Offering,
+    perk::{Perk, PerkName},
+    update::SurvivorId,
+};
+
+/// An equippable which may give luck
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LuckItem {
+    Perk(Perk),
+    Offering(Offering),
+}
+
+/// Where some of a survivor's luck came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LuckSource {
+    /// The chance every survivor has without any luck items
+    Base,
+    /// A survivor's perk or offering. Luck from another survivor's item is
+    /// luck which that item gives the whole team. The owner is unknown when
+    /// a record was made without a team around it.
+    Item {
+        owner: Option<SurvivorId>,
+        item: LuckItem,
+    },
+    /// A survivor's Up the Ante, which gives the whole team luck for each
+    /// survivor other than its owner who is alive
+    UpTheAnte {
+        owner: Option<SurvivorId>,
+        living_others: u8,
+    },
+    /// Deliverance or Wicked with its condition met, which puts certainty
+    /// in place of the survivor's own luck
+    Lock(PerkName),
+    /// Luck beyond certainty, which is lost
+    Cap,
+    /// Luck from records which were not made from items, and so carry no source
+    Untraced,
+}
+
+/// One source's share of a survivor's luck
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Getters)]
+pub struct LuckContribution {
+    source: LuckSource,
+    luck: Luck,
+}
+
+impl LuckContribution {
+    pub const fn new(source: LuckSource, luck: Luck) -> Self {
+        Self { source, luck }
+    }
+}
+
+/// Where a survivor's luck on each attempt came from. The contributions
+/// always sum to that luck, the lost luck of the cap included.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Getters)]
+pub struct LuckBreakdown {
+    contributions: Vec<LuckContribution>,
+}
+
+impl LuckBreakdown {
+    pub(crate) fn new(contributions: Vec<LuckContribution>) -> Self {
+        Self { contributions }
+    }
+
+    /// The survivor's luck on each attempt
+    pub fn total(&self) -> Luck {
+        self.contributions
+            .iter()
+            .fold(Luck::zero(), |acc, x| acc + x.luck)
+    }
+}
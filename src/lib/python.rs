@@ -0,0 +1,130 @@
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+
+use super::{
+    constants::misc as k,
+    offering::{Offering, OfferingSlot},
+    perk::{PerkName, Tier, TierSlot},
+    ruleset::Ruleset,
+    team::{Team, TeamSize},
+    update::{SurvivorId, SurvivorUpdate},
+};
+
+/// A team for Python. Survivors are counted from zero, and odds are
+/// calculated under the live game's rules.
+#[pyclass(name = "Team", eq, module = "hook_escape_calculator")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyTeam(Team);
+
+#[pymethods]
+impl PyTeam {
+    #[new]
+    #[pyo3(signature = (size = k::DEFAULT_TEAM_SIZE))]
+    fn new(size: usize) -> PyResult<Self> {
+        let size = TeamSize::try_new(size).map_err(|_| {
+            PyValueError::new_err(format!("a team has one to eight survivors, not {size}"))
+        })?;
+        Ok(Self(Team::with_size(size)))
+    }
+
+    #[getter]
+    fn size(&self) -> usize {
+        *self.0.size()
+    }
+
+    #[staticmethod]
+    fn from_code(code: &str) -> PyResult<Self> {
+        Team::from_code(code).map(Self).map_err(value_error)
+    }
+
+    fn to_code(&self) -> String {
+        self.0.to_code()
+    }
+
+    /// Equips a perk at the given tier, or removes it when the tier is None
+    fn set_perk(&mut self, survivor: usize, perk: PerkName, tier: Option<Tier>) -> PyResult<()> {
+        self.apply(
+            SurvivorUpdate::perk()
+                .id(survivor_id(survivor)?)
+                .perk(perk)
+                .tier(TierSlot::new(tier))
+                .call(),
+        )
+    }
+
+    #[pyo3(signature = (survivor, offering = None))]
+    fn set_offering(&mut self, survivor: usize, offering: Option<Offering>) -> PyResult<()> {
+        self.apply(
+            SurvivorUpdate::offering()
+                .id(survivor_id(survivor)?)
+                .offering(OfferingSlot::new(offering))
+                .call(),
+        )
+    }
+
+    fn set_alive(&mut self, survivor: usize, alive: bool) -> PyResult<()> {
+        self.apply(
+            SurvivorUpdate::living_status()
+                .id(survivor_id(survivor)?)
+                .alive(alive)
+                .call(),
+        )
+    }
+
+    fn set_lock_condition(&mut self, survivor: usize, met: bool) -> PyResult<()> {
+        self.apply(
+            SurvivorUpdate::lock_condition()
+                .id(survivor_id(survivor)?)
+                .met(met)
+                .call(),
+        )
+    }
+
+    /// Each survivor's chance of escaping on one attempt and on any
+    /// attempt, as a list of (single, total) tuples
+    fn luck_output(&self) -> Vec<(f64, f64)> {
+        self.0.luck_output(&Ruleset::default())
+    }
+
+    /// The same odds as `luck_output`, as a list of dicts with the keys
+    /// "single" and "total"
+    fn odds<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.luck_output()
+            .into_iter()
+            .map(|(single, total)| {
+                let dict = PyDict::new(py);
+                dict.set_item("single", single)?;
+                dict.set_item("total", total)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Team.from_code({:?})", self.0.to_code())
+    }
+}
+
+impl PyTeam {
+    /// A refused update leaves the team unchanged and is raised as a ValueError
+    fn apply(&mut self, update: SurvivorUpdate) -> PyResult<()> {
+        self.0.alter(update).map_err(value_error)
+    }
+}
+
+fn survivor_id(survivor: usize) -> PyResult<SurvivorId> {
+    SurvivorId::try_new(survivor)
+        .map_err(|_| PyValueError::new_err(format!("there is no survivor {survivor} in any team")))
+}
+
+fn value_error(e: impl std::error::Error) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+#[pymodule]
+fn hook_escape_calculator(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyTeam>()?;
+    m.add_class::<PerkName>()?;
+    m.add_class::<Tier>()?;
+    m.add_class::<Offering>()?;
+    Ok(())
+}
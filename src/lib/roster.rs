@@ -0,0 +1,159 @@
+use derive_getters::Getters;
+
+use super::{constants::misc as k, team::Team, update::SurvivorId};
+
+#[cfg(feature = "serde")]
+use super::team_form::TeamForm;
+
+/// What a survivor is called by the people at the table, and which
+/// character they play, if anyone has said.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Getters)]
+pub struct SurvivorLabel {
+    name: String,
+    character: Option<String>,
+}
+
+impl SurvivorLabel {
+    pub fn new(name: &str, character: Option<&str>) -> Self {
+        let mut label = Self::default();
+        label.set_name(name);
+        label.set_character(character);
+        label
+    }
+
+    /// Keeps at most `MAX_LABEL_CHARS` characters of the name
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.chars().take(k::MAX_LABEL_CHARS).collect();
+    }
+
+    /// Keeps at most `MAX_LABEL_CHARS` characters; an empty character is none
+    pub fn set_character(&mut self, character: Option<&str>) {
+        self.character = character
+            .filter(|x| !x.is_empty())
+            .map(|x| x.chars().take(k::MAX_LABEL_CHARS).collect());
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.name.is_empty() && self.character.is_none()
+    }
+}
+
+/// A label for every survivor a team may hold. Labels are kept beside a
+/// team rather than in it, so that a team stays a small `Copy` value for
+/// the calculations, and a survivor keeps their name when the team is
+/// resized.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Roster {
+    labels: [SurvivorLabel; k::TEAM_MAX_CAPACITY],
+}
+
+impl Roster {
+    pub fn get(&self, id: SurvivorId) -> &SurvivorLabel {
+        &self.labels[*id]
+    }
+
+    pub fn get_mut(&mut self, id: SurvivorId) -> &mut SurvivorLabel {
+        &mut self.labels[*id]
+    }
+
+    /// The name to show for a survivor, which is "Player n" until they are named
+    pub fn display_name(&self, id: SurvivorId) -> String {
+        match self.get(id).name.trim() {
+            "" => format!("Player {}", *id + 1),
+            name => name.to_owned(),
+        }
+    }
+
+    /// Whether every survivor of the team is unlabelled
+    pub fn is_blank_for(&self, team: &Team) -> bool {
+        team.ids().all(|id| self.get(id).is_blank())
+    }
+}
+
+/// A team along with what its survivors are called, for presets and
+/// exports. Only the labels of survivors in the team are carried.
+///
+/// With the `serde` feature it is written as a team whose survivors may
+/// each have a `name` and `character`, so a plain team reads as a named
+/// team with no names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "TeamForm", try_from = "TeamForm")
+)]
+pub struct NamedTeam {
+    pub team: Team,
+    pub roster: Roster,
+}
+
+impl NamedTeam {
+    pub fn new(team: Team, roster: Roster) -> Self {
+        Self { team, roster }
+    }
+}
+
+#[cfg(test)]
+pub mod arb {
+    use super::*;
+    use crate::team;
+    use proptest::prelude::*;
+
+    pub fn label() -> impl Strategy<Value = SurvivorLabel> {
+        (
+            "[A-Za-z0-9 é]{0,32}",
+            prop::option::of("[A-Za-z0-9 é]{1,32}"),
+        )
+            .prop_map(|(name, character)| SurvivorLabel::new(&name, character.as_deref()))
+    }
+
+    prop_compose! {
+        /// A named team whose survivors outside the team are unlabelled
+        pub fn named_team()(
+            team in team::arb::team(),
+            labels in prop::collection::vec(label(), k::TEAM_MAX_CAPACITY)
+        ) -> NamedTeam {
+            let mut roster = Roster::default();
+            for (id, label) in team.ids().zip(labels) {
+                *roster.get_mut(id) = label;
+            }
+            NamedTeam::new(team, roster)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first() -> SurvivorId {
+        SurvivorId::try_new(0).expect("Zero is a survivor.")
+    }
+
+    #[test]
+    fn unnamed_survivors_shown_by_number() {
+        let mut roster = Roster::default();
+        roster.get_mut(first()).set_name("   ");
+
+        assert_eq!(roster.display_name(first()), "Player 1");
+        roster.get_mut(first()).set_name(" Meg main ");
+        assert_eq!(roster.display_name(first()), "Meg main")
+    }
+
+    #[test]
+    fn long_labels_cut_short() {
+        let long = "é".repeat(k::MAX_LABEL_CHARS + 10);
+        let label = SurvivorLabel::new(&long, Some(&long));
+
+        assert_eq!(label.name().chars().count(), k::MAX_LABEL_CHARS);
+        assert_eq!(
+            label.character().as_ref().map(|x| x.chars().count()),
+            Some(k::MAX_LABEL_CHARS)
+        )
+    }
+
+    #[test]
+    fn empty_character_is_none() {
+        assert!(SurvivorLabel::new("", Some("")).is_blank())
+    }
+}
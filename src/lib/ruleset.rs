@@ -0,0 +1,421 @@
+use derive_getters::Getters;
+use derive_more::Display;
+use num_traits::{One, Zero};
+
+use crate::constants::{misc, offering_luck, perk_luck};
+
+use super::{
+    luck_record::Luck,
+    perk::{PerkName, Tier},
+};
+
+/// The game balance values which the luck calculation depends on.
+/// The default ruleset is the live game's, as written in `constants`.
+///
+/// Rulesets may be read from TOML or JSON, in which every luck value
+/// is written as a percentage with at most two decimal places. Any
+/// value left out of a file keeps its default.
+#[derive(Debug, Clone, Copy, PartialEq, Getters)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct Ruleset {
+    #[cfg_attr(feature = "serde", serde(with = "percent"))]
+    base_unhook_chance: Luck,
+    base_unhook_attempts: i8,
+    offering: OfferingValues,
+    slippery_meat: SlipperyMeatValues,
+    up_the_ante: TierValues,
+    lock_perks: LockPerks,
+}
+
+/// Luck given by the luck offerings of each strength. Personal
+/// and global offerings of the same strength give the same luck.
+#[derive(Debug, Clone, Copy, PartialEq, Getters)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct OfferingValues {
+    #[cfg_attr(feature = "serde", serde(with = "percent"))]
+    slight: Luck,
+    #[cfg_attr(feature = "serde", serde(with = "percent"))]
+    moderate: Luck,
+    #[cfg_attr(feature = "serde", serde(with = "percent"))]
+    great: Luck,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Getters)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct SlipperyMeatValues {
+    luck: TierValues,
+    additional_unhooks: i8,
+}
+
+/// Whether each perk which locks luck is in the game. A perk which is
+/// not, as in the versions before its release, gives no luck at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Getters)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct LockPerks {
+    deliverance: bool,
+    wicked: bool,
+}
+
+impl LockPerks {
+    pub const fn new(deliverance: bool, wicked: bool) -> Self {
+        Self {
+            deliverance,
+            wicked,
+        }
+    }
+    /// Whether the perk locks luck once its condition is met
+    pub const fn locks(&self, perk: PerkName) -> bool {
+        match perk {
+            PerkName::Deliverance => self.deliverance,
+            PerkName::Wicked => self.wicked,
+            PerkName::SlipperyMeat | PerkName::UpTheAnte | PerkName::Other => false,
+        }
+    }
+}
+
+/// A luck value for each tier of a perk. A tier table in a file must
+/// give every tier, as there is no sensible default for only some of them.
+#[derive(Debug, Clone, Copy, PartialEq, Getters)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct TierValues {
+    #[cfg_attr(feature = "serde", serde(with = "percent"))]
+    one: Luck,
+    #[cfg_attr(feature = "serde", serde(with = "percent"))]
+    two: Luck,
+    #[cfg_attr(feature = "serde", serde(with = "percent"))]
+    three: Luck,
+}
+
+impl TierValues {
+    pub const fn new(one: Luck, two: Luck, three: Luck) -> Self {
+        Self { one, two, three }
+    }
+    pub const fn get(&self, tier: Tier) -> Luck {
+        match tier {
+            Tier::One => self.one,
+            Tier::Two => self.two,
+            Tier::Three => self.three,
+        }
+    }
+    fn iter(&self) -> impl Iterator<Item = Luck> {
+        [self.one, self.two, self.three].into_iter()
+    }
+}
+
+impl Ruleset {
+    pub(crate) const fn const_default() -> Self {
+        Self {
+            base_unhook_chance: misc::BASE_UNHOOK_CHANCE,
+            base_unhook_attempts: misc::BASE_UNHOOK_ATTEMPTS,
+            offering: OfferingValues {
+                slight: offering_luck::SLIGHT_LUCK,
+                moderate: offering_luck::MODERATE_LUCK,
+                great: offering_luck::GREAT_LUCK,
+            },
+            slippery_meat: SlipperyMeatValues {
+                luck: TierValues::new(
+                    perk_luck::SM_TIER1,
+                    perk_luck::SM_TIER2,
+                    perk_luck::SM_TIER3,
+                ),
+                additional_unhooks: perk_luck::SM_ADDITIONAL_UNHOOKS,
+            },
+            up_the_ante: TierValues::new(
+                perk_luck::UTA_TIER1,
+                perk_luck::UTA_TIER2,
+                perk_luck::UTA_TIER3,
+            ),
+            lock_perks: LockPerks::new(true, true),
+        }
+    }
+
+    pub(crate) const fn with_up_the_ante(self, up_the_ante: TierValues) -> Self {
+        Self {
+            up_the_ante,
+            ..self
+        }
+    }
+
+    pub(crate) const fn with_lock_perks(self, lock_perks: LockPerks) -> Self {
+        Self { lock_perks, ..self }
+    }
+
+    /// Ensures that every luck value is a probability, that every
+    /// survivor has at least one unhook attempt, and that Slippery Meat
+    /// adds a countable number of attempts.
+    pub fn validate(self) -> Result<Self, RulesetError> {
+        let offerings = [
+            self.offering.slight,
+            self.offering.moderate,
+            self.offering.great,
+        ];
+        let all_luck = std::iter::once(self.base_unhook_chance)
+            .chain(offerings)
+            .chain(self.slippery_meat.luck.iter())
+            .chain(self.up_the_ante.iter());
+
+        if !all_luck
+            .into_iter()
+            .all(|luck| (Luck::zero()..=Luck::one()).contains(&luck))
+        {
+            return Err(RulesetError::LuckOutOfRange);
+        }
+        if self.base_unhook_attempts < 1 {
+            return Err(RulesetError::NoUnhookAttempts);
+        }
+        if self.slippery_meat.additional_unhooks < 0 {
+            return Err(RulesetError::NegativeAdditionalUnhooks);
+        }
+        if self
+            .base_unhook_attempts
+            .checked_add(self.slippery_meat.additional_unhooks)
+            .is_none()
+        {
+            return Err(RulesetError::TooManyUnhookAttempts);
+        }
+        Ok(self)
+    }
+}
+
+impl std::default::Default for Ruleset {
+    fn default() -> Self {
+        Self::const_default()
+    }
+}
+
+impl std::default::Default for OfferingValues {
+    fn default() -> Self {
+        Ruleset::const_default().offering
+    }
+}
+
+impl std::default::Default for SlipperyMeatValues {
+    fn default() -> Self {
+        Ruleset::const_default().slippery_meat
+    }
+}
+
+impl std::default::Default for LockPerks {
+    fn default() -> Self {
+        Ruleset::const_default().lock_perks
+    }
+}
+
+#[derive(Debug, Display)]
+pub enum RulesetError {
+    #[display("every luck value must be a percentage from 0 to 100")]
+    LuckOutOfRange,
+    #[display("survivors must have at least one base unhook attempt")]
+    NoUnhookAttempts,
+    #[display("Slippery Meat may not take unhook attempts away")]
+    NegativeAdditionalUnhooks,
+    #[display("survivors may have at most {} unhook attempts", i8::MAX)]
+    TooManyUnhookAttempts,
+    #[cfg(feature = "rules-file")]
+    #[display("--rules must be followed by the path of a ruleset file")]
+    MissingPath,
+    #[cfg(feature = "rules-file")]
+    #[display("could not read ruleset file: {_0}")]
+    Io(std::io::Error),
+    #[cfg(feature = "rules-file")]
+    #[display("could not parse TOML ruleset: {_0}")]
+    Toml(toml::de::Error),
+    #[cfg(feature = "rules-file")]
+    #[display("could not parse JSON ruleset: {_0}")]
+    Json(serde_json::Error),
+}
+
+impl std::error::Error for RulesetError {}
+
+#[cfg(feature = "rules-file")]
+impl Ruleset {
+    pub fn from_toml(s: &str) -> Result<Self, RulesetError> {
+        toml::from_str::<Self>(s)
+            .map_err(RulesetError::Toml)
+            .and_then(Self::validate)
+    }
+    pub fn from_json(s: &str) -> Result<Self, RulesetError> {
+        serde_json::from_str::<Self>(s)
+            .map_err(RulesetError::Json)
+            .and_then(Self::validate)
+    }
+    /// Reads a ruleset file, which is parsed as JSON if it has a `.json`
+    /// extension and as TOML otherwise.
+    pub fn load(path: &std::path::Path) -> Result<Self, RulesetError> {
+        let contents = std::fs::read_to_string(path).map_err(RulesetError::Io)?;
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("json") => Self::from_json(&contents),
+            _ => Self::from_toml(&contents),
+        }
+    }
+}
+
+/// Reads the ruleset file named by the argument which follows `--rules`
+/// on a command line, refusing a `--rules` given without a path.
+#[cfg(feature = "rules-file")]
+pub fn from_path_arg(path: Option<String>) -> Result<Ruleset, RulesetError> {
+    let path = path.ok_or(RulesetError::MissingPath)?;
+    Ruleset::load(std::path::Path::new(&path))
+}
+
+/// Luck is stored as an exact probability but written in files as a
+/// percentage, which is read to the nearest hundredth of a percent.
+#[cfg(feature = "serde")]
+mod percent {
+    use num_traits::ToPrimitive;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    use super::Luck;
+
+    const HUNDREDTHS_OF_PERCENT: i64 = 10_000;
+
+    pub fn serialize<S: Serializer>(luck: &Luck, serializer: S) -> Result<S::Ok, S::Error> {
+        let percent = (luck * 100)
+            .to_f64()
+            .ok_or_else(|| serde::ser::Error::custom("luck cannot be written as a percentage"))?;
+        serializer.serialize_f64(percent)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Luck, D::Error> {
+        let percent = f64::deserialize(deserializer)?;
+        let hundredths = (percent * 100.).round();
+        if !hundredths.is_finite() || hundredths.abs() > i64::MAX as f64 {
+            return Err(D::Error::custom("percentage is too large"));
+        }
+        Ok(Luck::new(hundredths as i64, HUNDREDTHS_OF_PERCENT))
+    }
+}
+
+#[cfg(all(test, feature = "rules-file"))]
+mod tests {
+    use super::*;
+    use crate::constants::percent;
+
+    #[test]
+    fn default_round_trips_through_toml() {
+        let rules = Ruleset::default();
+        let text = toml::to_string(&rules).expect("Default ruleset serializes.");
+
+        assert_eq!(Ruleset::from_toml(&text).ok(), Some(rules))
+    }
+
+    #[test]
+    fn partial_file_keeps_defaults() {
+        let rules = Ruleset::from_toml(
+            "base_unhook_attempts = 4\n\
+             [slippery_meat.luck]\n\
+             one = 3\n\
+             two = 4\n\
+             three = 5\n",
+        )
+        .expect("Partial ruleset is valid.");
+
+        assert_eq!(rules.base_unhook_attempts(), 4);
+        assert_eq!(rules.slippery_meat().luck().get(Tier::Three), percent(5));
+        assert_eq!(
+            rules.slippery_meat().additional_unhooks(),
+            Ruleset::default().slippery_meat().additional_unhooks()
+        );
+        assert_eq!(rules.up_the_ante(), Ruleset::default().up_the_ante());
+    }
+
+    #[test]
+    fn lock_perks_may_be_left_out() {
+        let rules = Ruleset::from_toml("[lock_perks]\nwicked = false").expect("Ruleset is valid.");
+
+        assert!(rules.lock_perks().locks(PerkName::Deliverance));
+        assert!(!rules.lock_perks().locks(PerkName::Wicked));
+        assert!(!rules.lock_perks().locks(PerkName::SlipperyMeat))
+    }
+
+    #[test]
+    fn json_is_read_as_percentages() {
+        let rules =
+            Ruleset::from_json(r#"{ "base_unhook_chance": 10 }"#).expect("JSON ruleset is valid.");
+
+        assert_eq!(*rules.base_unhook_chance(), percent(10))
+    }
+
+    #[test]
+    fn fractional_percentages_are_exact() {
+        let rules = Ruleset::from_toml("base_unhook_chance = 4.25").expect("4.25% is valid.");
+
+        assert_eq!(*rules.base_unhook_chance(), Luck::new(17, 400))
+    }
+
+    #[test]
+    fn invalid_rules_refused() {
+        assert!(matches!(
+            Ruleset::from_toml("base_unhook_chance = 120"),
+            Err(RulesetError::LuckOutOfRange)
+        ));
+        assert!(matches!(
+            Ruleset::from_toml("base_unhook_attempts = 0"),
+            Err(RulesetError::NoUnhookAttempts)
+        ));
+        assert!(matches!(
+            Ruleset::from_toml("bonus_luck = 5"),
+            Err(RulesetError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn unhook_attempts_bounded() {
+        assert!(matches!(
+            Ruleset::from_toml("[slippery_meat]\nadditional_unhooks = -3"),
+            Err(RulesetError::NegativeAdditionalUnhooks)
+        ));
+        assert!(matches!(
+            Ruleset::from_toml(
+                "base_unhook_attempts = 100\n[slippery_meat]\nadditional_unhooks = 28"
+            ),
+            Err(RulesetError::TooManyUnhookAttempts)
+        ));
+        assert!(
+            Ruleset::from_toml(
+                "base_unhook_attempts = 100\n[slippery_meat]\nadditional_unhooks = 27"
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn path_arg_required() {
+        assert!(matches!(
+            from_path_arg(None),
+            Err(RulesetError::MissingPath)
+        ));
+        assert!(matches!(
+            from_path_arg(Some(String::from("no-such-rules.toml"))),
+            Err(RulesetError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn partial_tier_table_refused() {
+        assert!(matches!(
+            Ruleset::from_toml("[up_the_ante]\nthree = 5"),
+            Err(RulesetError::Toml(_))
+        ));
+    }
+}
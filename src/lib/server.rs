@@ -0,0 +1,330 @@
+use std::{
+    convert::Infallible,
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+};
+use serde::Serialize;
+
+use super::{
+    offering::Offering,
+    perk::{PerkName, Tier},
+    roster::NamedTeam,
+    ruleset::Ruleset,
+    team::Team,
+    update::SurvivorUpdate,
+};
+
+/// Shared by every request: the rules of every calculation and the team,
+/// with its survivors' names, which the session endpoint builds up.
+#[derive(Debug, Clone)]
+struct ServerState {
+    rules: Ruleset,
+    session: Arc<Mutex<NamedTeam>>,
+}
+
+impl ServerState {
+    /// Changes the session team and reports the team it leaves behind,
+    /// holding the lock throughout so that a change made by another
+    /// request in between is never reported in its place. A poisoned
+    /// lock still holds a whole team, since a refused update leaves it
+    /// unchanged.
+    fn change_session<E>(
+        &self,
+        change: impl FnOnce(&mut NamedTeam) -> Result<(), E>,
+    ) -> Result<SessionReport, E> {
+        let mut team = self.session.lock().unwrap_or_else(PoisonError::into_inner);
+        change(&mut team)?;
+        Ok(SessionReport {
+            odds: OddsReport::new(&team.team, &self.rules),
+            team: team.clone(),
+        })
+    }
+}
+
+/// Each survivor's chance of escaping on one attempt and on any
+/// attempt, by survivor id
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OddsReport {
+    survivors: Vec<SurvivorOdds>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+struct SurvivorOdds {
+    single: f64,
+    total: f64,
+}
+
+impl OddsReport {
+    fn new(team: &Team, rules: &Ruleset) -> Self {
+        let survivors = team
+            .luck_output(rules)
+            .into_iter()
+            .map(|(single, total)| SurvivorOdds { single, total })
+            .collect();
+        Self { survivors }
+    }
+}
+
+/// Everything a request may name
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Catalog {
+    perks: Vec<PerkName>,
+    tiers: Vec<Tier>,
+    offerings: Vec<Offering>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionReport {
+    team: NamedTeam,
+    odds: OddsReport,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct ErrorReport {
+    error: String,
+}
+
+/// The routes of the calculation API:
+/// - `POST /odds` takes a team and gives its odds
+/// - `GET /catalog` lists the perks, tiers and offerings
+/// - `GET /session` gives the session team and its odds
+/// - `POST /session` applies one survivor update to the session team
+/// - `PUT /session` replaces the session team and its survivors' names
+/// - `DELETE /session` resets the session team
+pub fn router(rules: Ruleset) -> Router {
+    let state = ServerState {
+        rules,
+        session: Arc::default(),
+    };
+    Router::new()
+        .route("/odds", post(odds))
+        .route("/catalog", get(catalog))
+        .route(
+            "/session",
+            get(session)
+                .post(update_session)
+                .put(replace_session)
+                .delete(reset_session),
+        )
+        .with_state(state)
+}
+
+/// Serves the API on localhost until the process ends
+pub async fn serve(port: u16, rules: Ruleset) -> std::io::Result<()> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    axum::serve(listener, router(rules)).await
+}
+
+async fn odds(State(state): State<ServerState>, Json(team): Json<Team>) -> Json<OddsReport> {
+    Json(OddsReport::new(&team, &state.rules))
+}
+
+async fn catalog() -> Json<Catalog> {
+    Json(Catalog {
+        perks: PerkName::iterator().collect(),
+        tiers: Tier::iterator().collect(),
+        offerings: Offering::iterator().collect(),
+    })
+}
+
+async fn session(State(state): State<ServerState>) -> Json<SessionReport> {
+    let Ok(report) = state.change_session(|_| Ok::<(), Infallible>(()));
+    Json(report)
+}
+
+/// A refused update leaves the session as it was and is answered with
+/// the reason it was refused.
+async fn update_session(
+    State(state): State<ServerState>,
+    Json(update): Json<SurvivorUpdate>,
+) -> Result<Json<SessionReport>, (StatusCode, Json<ErrorReport>)> {
+    match state.change_session(|x| x.team.alter(update)) {
+        Ok(report) => Ok(Json(report)),
+        Err(e) => Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ErrorReport {
+                error: e.to_string(),
+            }),
+        )),
+    }
+}
+
+/// A team given without names leaves its survivors unnamed
+async fn replace_session(
+    State(state): State<ServerState>,
+    Json(team): Json<NamedTeam>,
+) -> Json<SessionReport> {
+    let Ok(report) = state.change_session(|x| {
+        *x = team;
+        Ok::<(), Infallible>(())
+    });
+    Json(report)
+}
+
+async fn reset_session(State(state): State<ServerState>) -> Json<SessionReport> {
+    let Ok(report) = state.change_session(|x| {
+        *x = NamedTeam::default();
+        Ok::<(), Infallible>(())
+    });
+    Json(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Method, Request},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+
+    /// Sends one request to the router in process, giving the status and JSON body
+    async fn send(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |x| Body::from(x.to_string())))
+            .expect("Test requests are well formed.");
+        let response = app
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("Routers never fail to answer.");
+        let status = response.status();
+        let bytes = response
+            .into_body()
+            .collect()
+            .await
+            .expect("Bodies are read whole.")
+            .to_bytes();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn odds_of_posted_team() {
+        let app = router(Ruleset::default());
+        let team = json!({ "survivors": [
+            { "perks": [{ "name": "up-the-ante", "tier": "three" }, null, null, null] },
+            {}
+        ] });
+
+        let (status, body) = send(&app, Method::POST, "/odds", Some(team.clone())).await;
+        let expected = OddsReport::new(
+            &serde_json::from_value(team).expect("The test team is valid."),
+            &Ruleset::default(),
+        );
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::to_value(expected).expect("Reports serialize.")
+        );
+        assert_eq!(body["survivors"].as_array().map(Vec::len), Some(2))
+    }
+
+    #[tokio::test]
+    async fn invalid_team_refused() {
+        let app = router(Ruleset::default());
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/odds",
+            Some(json!({ "survivors": [] })),
+        )
+        .await;
+
+        assert!(status.is_client_error())
+    }
+
+    #[tokio::test]
+    async fn catalog_names_every_item() {
+        let app = router(Ruleset::default());
+
+        let (status, body) = send(&app, Method::GET, "/catalog", None).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["tiers"], json!(["one", "two", "three"]));
+        assert!(
+            body["perks"]
+                .as_array()
+                .is_some_and(|x| x.contains(&json!("slippery-meat")))
+        );
+        assert_eq!(
+            body["offerings"].as_array().map(Vec::len),
+            Some(Offering::iterator().count())
+        )
+    }
+
+    #[tokio::test]
+    async fn session_keeps_updates() {
+        let app = router(Ruleset::default());
+        let update = json!({ "kind": "offering", "survivor": 2, "offering": "salty-lips" });
+
+        let (status, posted) = send(&app, Method::POST, "/session", Some(update)).await;
+        let (_, fetched) = send(&app, Method::GET, "/session", None).await;
+        let (_, reset) = send(&app, Method::DELETE, "/session", None).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(posted, fetched);
+        assert_eq!(
+            fetched["team"]["survivors"][2]["offering"],
+            json!("salty-lips")
+        );
+        assert_eq!(reset["team"]["survivors"][2]["offering"], Value::Null)
+    }
+
+    #[tokio::test]
+    async fn session_keeps_names() {
+        let app = router(Ruleset::default());
+        let team = json!({ "survivors": [
+            { "name": "Ana", "character": "Meg" },
+            { "name": "Bo" }
+        ] });
+        let update = json!({ "kind": "alive", "survivor": 1, "alive": false });
+
+        let (status, replaced) = send(&app, Method::PUT, "/session", Some(team)).await;
+        let (_, updated) = send(&app, Method::POST, "/session", Some(update)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replaced["team"]["survivors"][0]["character"], json!("Meg"));
+        assert_eq!(updated["team"]["survivors"][1]["name"], json!("Bo"));
+        assert_eq!(
+            updated["odds"]["survivors"].as_array().map(Vec::len),
+            Some(2)
+        )
+    }
+
+    #[tokio::test]
+    async fn refused_session_update_explained() {
+        let app = router(Ruleset::default());
+        let (_, before) = send(&app, Method::GET, "/session", None).await;
+        let update = json!({ "kind": "alive", "survivor": 6, "alive": false });
+
+        let (status, body) = send(&app, Method::POST, "/session", Some(update)).await;
+        let (_, after) = send(&app, Method::GET, "/session", None).await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"], json!("player 7 is not in the team"));
+        assert_eq!(before, after)
+    }
+}
@@ -0,0 +1,248 @@
+use derive_getters::Getters;
+use derive_more::Display;
+
+use super::{
+    roster::{NamedTeam, SurvivorLabel},
+    update::{SurvivorId, SurvivorUpdate, UpdateError},
+};
+
+/// One change to the team of a session or to its survivors' names
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionChange {
+    /// An update to one survivor
+    Update(SurvivorUpdate),
+    /// The whole team and its names replaced at once, as when a session
+    /// starts, a preset is loaded or the team is resized. A team written
+    /// without names reads as one whose survivors are unnamed.
+    Team(Box<NamedTeam>),
+    /// A new name and character for one survivor
+    Label {
+        #[serde(with = "survivor_id")]
+        survivor: SurvivorId,
+        name: String,
+        character: Option<String>,
+    },
+}
+
+impl SessionChange {
+    /// The change which replaces the team and its names
+    pub fn team(named: NamedTeam) -> Self {
+        SessionChange::Team(Box::new(named))
+    }
+
+    /// The change which gives a survivor the label
+    pub fn label(survivor: SurvivorId, label: &SurvivorLabel) -> Self {
+        SessionChange::Label {
+            survivor,
+            name: label.name().clone(),
+            character: label.character().clone(),
+        }
+    }
+
+    pub fn apply(&self, named: &mut NamedTeam) -> Result<(), UpdateError> {
+        match self {
+            SessionChange::Update(update) => named.team.alter(*update),
+            SessionChange::Team(replacement) => {
+                *named = NamedTeam::clone(replacement);
+                Ok(())
+            }
+            SessionChange::Label {
+                survivor,
+                name,
+                character,
+            } => {
+                if !named.team.contains(*survivor) {
+                    return Err(UpdateError::NotInTeam(*survivor));
+                }
+                *named.roster.get_mut(*survivor) = SurvivorLabel::new(name, character.as_deref());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A survivor id written as its number
+mod survivor_id {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    use crate::update::SurvivorId;
+
+    pub fn serialize<S: Serializer>(id: &SurvivorId, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(**id as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SurvivorId, D::Error> {
+        SurvivorId::try_new(usize::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// A change and when it was made, written as one JSON line of a session log
+#[derive(Debug, Clone, PartialEq, Eq, Getters, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SessionEvent {
+    /// Milliseconds since the Unix epoch
+    time_ms: u64,
+    change: SessionChange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum SessionLogError {
+    #[display("line {line} of the session log is not an event: {message}")]
+    Json { line: usize, message: String },
+    #[display("event {event} of the session log was refused: {error}")]
+    Refused { event: usize, error: UpdateError },
+}
+
+impl std::error::Error for SessionLogError {}
+
+impl SessionEvent {
+    pub fn new(time_ms: u64, change: SessionChange) -> Self {
+        Self { time_ms, change }
+    }
+
+    /// The event as a line of JSON, without the line break
+    pub fn to_line(&self) -> String {
+        serde_json::to_string(self).expect("Events always serialize.")
+    }
+}
+
+/// Reads a session log of one event per line, skipping blank lines
+pub fn read_log(text: &str) -> Result<Vec<SessionEvent>, SessionLogError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| SessionLogError::Json {
+                line: i + 1,
+                message: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The named team after every event, starting from the default team
+pub fn replay(events: &[SessionEvent]) -> Result<NamedTeam, SessionLogError> {
+    Ok(replay_steps(events)?.pop().unwrap_or_default())
+}
+
+/// The named team after each event, starting from the default team, so
+/// that any point of a session may be shown again
+pub fn replay_steps(events: &[SessionEvent]) -> Result<Vec<NamedTeam>, SessionLogError> {
+    let mut team = NamedTeam::default();
+    events
+        .iter()
+        .enumerate()
+        .map(|(i, event)| {
+            event
+                .change
+                .apply(&mut team)
+                .map_err(|error| SessionLogError::Refused { event: i, error })?;
+            Ok(team.clone())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{roster, update};
+    use proptest::prelude::*;
+
+    fn first() -> SurvivorId {
+        SurvivorId::try_new(0).expect("Zero is a survivor.")
+    }
+
+    proptest! {
+        #[test]
+        fn replay_rebuilds_every_step(
+            start in roster::arb::named_team(),
+            updates in prop::collection::vec(update::arb::survivor_update_data(), 0..30),
+            labels in prop::collection::vec(roster::arb::label(), 0..5)
+        ) {
+            let mut team = start.clone();
+            let mut expected = vec![start.clone()];
+            let mut events = vec![SessionEvent::new(0, SessionChange::team(start))];
+            let changes = updates
+                .into_iter()
+                .map(SessionChange::Update)
+                .chain(labels.iter().map(|x| SessionChange::label(first(), x)));
+            for (i, change) in changes.enumerate() {
+                // A session only records the changes its team accepted
+                if change.apply(&mut team).is_ok() {
+                    expected.push(team.clone());
+                    events.push(SessionEvent::new(i as u64 + 1, change));
+                }
+            }
+            let text: String = events.iter().map(|x| x.to_line() + "\n").collect();
+
+            let read = read_log(&text).expect("Written logs are read back.");
+
+            prop_assert_eq!(&read, &events);
+            prop_assert_eq!(replay_steps(&read), Ok(expected));
+            prop_assert_eq!(replay(&read), Ok(team));
+        }
+    }
+
+    #[test]
+    fn empty_log_replays_default_team() {
+        assert_eq!(read_log("\n  \n"), Ok(Vec::new()));
+        assert_eq!(replay(&[]), Ok(NamedTeam::default()))
+    }
+
+    #[test]
+    fn unnamed_team_replays_without_names() {
+        let mut named = NamedTeam::default();
+        named.roster.get_mut(first()).set_name("Meg main");
+        let events = read_log(&format!(
+            "{}\n{}\n",
+            r#"{ "time_ms": 1, "change": { "team": { "survivors": [{}, {}, {}, {}] } } }"#,
+            SessionEvent::new(2, SessionChange::label(first(), named.roster.get(first())))
+                .to_line()
+        ))
+        .expect("The events are well formed.");
+
+        assert_eq!(replay_steps(&events), Ok(vec![NamedTeam::default(), named]))
+    }
+
+    #[test]
+    fn bad_lines_reported() {
+        let text = format!(
+            "{}\n\nnot json\n",
+            SessionEvent::new(5, SessionChange::team(NamedTeam::default())).to_line()
+        );
+
+        assert!(matches!(
+            read_log(&text),
+            Err(SessionLogError::Json { line: 3, .. })
+        ))
+    }
+
+    #[test]
+    fn refused_events_reported() {
+        let events = read_log(
+            r#"{ "time_ms": 1, "change": { "update": { "kind": "alive", "survivor": 6, "alive": false } } }"#,
+        )
+        .expect("The event is well formed.");
+
+        assert!(matches!(
+            replay(&events),
+            Err(SessionLogError::Refused {
+                event: 0,
+                error: UpdateError::NotInTeam(_)
+            })
+        ))
+    }
+
+    #[test]
+    fn labels_outside_team_refused() {
+        let id = SurvivorId::try_new(6).expect("Six is a survivor.");
+        let mut named = NamedTeam::default();
+
+        let result =
+            SessionChange::label(id, &SurvivorLabel::new("Dwight", None)).apply(&mut named);
+
+        assert_eq!(result, Err(UpdateError::NotInTeam(id)));
+        assert_eq!(named, NamedTeam::default())
+    }
+}
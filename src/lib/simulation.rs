@@ -0,0 +1,263 @@
+use num_traits::{One, Zero};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    luck_record::Luck, offering::Offering, perk::PerkName, player::Player, ruleset::Ruleset,
+    team::Team,
+};
+
+/// The z-score of a two-sided 95% confidence interval
+pub const Z_95: f64 = 1.959_964;
+
+/// How often a survivor escaped across the trials of a simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscapeRate {
+    escapes: u32,
+    trials: u32,
+}
+
+impl EscapeRate {
+    pub const fn escapes(&self) -> u32 {
+        self.escapes
+    }
+    pub const fn trials(&self) -> u32 {
+        self.trials
+    }
+    pub fn rate(&self) -> f64 {
+        match self.trials {
+            0 => 0.,
+            n => f64::from(self.escapes) / f64::from(n),
+        }
+    }
+    /// The Wilson score interval around the escape rate for the given
+    /// z-score, such as `Z_95`. Unlike the normal approximation it stays
+    /// within [0, 1] and is sensible for rates near either end.
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        if self.trials == 0 {
+            return (0., 1.);
+        }
+        let n = f64::from(self.trials);
+        let p = self.rate();
+        let z2 = z * z;
+
+        let centre = (p + z2 / (2. * n)) / (1. + z2 / n);
+        let spread = z / (1. + z2 / n) * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt();
+        ((centre - spread).max(0.), (centre + spread).min(1.))
+    }
+}
+
+impl Team {
+    /// Rolls every survivor's self-unhook attempts `trials` times, giving
+    /// each survivor's empirical rate of escaping on any attempt. The same
+    /// seed always rolls the same attempts.
+    ///
+    /// Each survivor's luck and attempts are worked out here from their
+    /// items and the ruleset, apart from the luck records, so that the
+    /// simulation checks how luck is added up as well as the odds.
+    pub fn simulate(&self, rules: &Ruleset, trials: u32, seed: u64) -> Vec<EscapeRate> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let global = self
+            .list()
+            .enumerate()
+            .fold(*rules.base_unhook_chance(), |acc, (id, player)| {
+                acc + global_luck(self, id, player, rules)
+            });
+
+        self.list()
+            .map(|player| {
+                let luck = (global + personal_luck(player, rules)).min(Luck::one());
+                let attempts = attempts(player, rules);
+                let escapes = (0..trials)
+                    .filter(|_| (0..attempts).any(|_| roll(&mut rng, luck)))
+                    .count();
+                EscapeRate {
+                    escapes: u32::try_from(escapes).expect("Escapes never outnumber trials."),
+                    trials,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The luck a survivor gives every survivor: a salt offering, and Up the
+/// Ante for each other living survivor while its owner is alive
+fn global_luck(team: &Team, id: usize, player: &Player, rules: &Ruleset) -> Luck {
+    let offering = match **player.offering() {
+        Some(Offering::SaltPouch) => *rules.offering().slight(),
+        Some(Offering::SaltStatuette) => *rules.offering().moderate(),
+        Some(Offering::SaltyLips) => *rules.offering().great(),
+        _ => Luck::zero(),
+    };
+    let living_others = team
+        .list()
+        .enumerate()
+        .filter(|(other, x)| *other != id && x.is_alive())
+        .count();
+    let up_the_ante = match (player.is_alive(), player.get_perk_tier(PerkName::UpTheAnte)) {
+        (true, Some(tier)) => rules.up_the_ante().get(*tier) * living_others as i64,
+        _ => Luck::zero(),
+    };
+    offering + up_the_ante
+}
+
+/// The luck a survivor gives only themself: a chalk offering and Slippery
+/// Meat, or certainty once the condition of Deliverance or Wicked is met,
+/// if the rules have that perk in the game
+fn personal_luck(player: &Player, rules: &Ruleset) -> Luck {
+    let locked = player.lock_condition_met()
+        && player
+            .equipped_perk_names()
+            .any(|x| rules.lock_perks().locks(x));
+    if locked {
+        return Luck::one();
+    }
+    let offering = match **player.offering() {
+        Some(Offering::ChalkPouch) => *rules.offering().slight(),
+        Some(Offering::CreamPouch) => *rules.offering().moderate(),
+        Some(Offering::IvoryPouch) => *rules.offering().great(),
+        _ => Luck::zero(),
+    };
+    let slippery_meat = player
+        .get_perk_tier(PerkName::SlipperyMeat)
+        .map_or(Luck::zero(), |tier| rules.slippery_meat().luck().get(*tier));
+    offering + slippery_meat
+}
+
+fn attempts(player: &Player, rules: &Ruleset) -> u8 {
+    let additional = match player.get_perk_tier(PerkName::SlipperyMeat) {
+        Some(_) => rules.slippery_meat().additional_unhooks(),
+        None => 0,
+    };
+    let attempts = i16::from(rules.base_unhook_attempts()) + i16::from(additional);
+    u8::try_from(attempts.max(0)).expect("The sum of two i8 values is below 256.")
+}
+
+/// One self-unhook attempt, which succeeds with exactly the chance `luck`
+fn roll(rng: &mut impl Rng, luck: Luck) -> bool {
+    let (numer, denom) = luck.into();
+    rng.gen_range(0..denom) < numer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        perk::{PerkName, Tier, TierSlot},
+        team,
+        update::{SurvivorId, SurvivorUpdate},
+    };
+    use num_traits::ToPrimitive;
+    use proptest::prelude::*;
+
+    /// Wide enough that an honest simulation essentially never falls outside it
+    const Z_TOLERANCE: f64 = 5.;
+
+    fn agrees_with_analytic(team: &Team, trials: u32, seed: u64) -> bool {
+        let rules = Ruleset::default();
+        let exact = team.exact_luck_output(&rules);
+        let simulated = team.simulate(&rules, trials, seed);
+
+        exact.len() == simulated.len()
+            && exact.iter().zip(&simulated).all(|((_, total), rate)| {
+                let (low, high) = rate.confidence_interval(Z_TOLERANCE);
+                let total = total.to_f64().expect("Chances are between 0 and 1.");
+                (low..=high).contains(&total)
+            })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        #[test]
+        fn simulation_agrees_with_luck_output(team in team::arb::team(), seed in any::<u64>()) {
+            prop_assert!(agrees_with_analytic(&team, 5_000, seed))
+        }
+    }
+
+    #[test]
+    fn up_the_ante_team_agrees_with_luck_output() {
+        let mut team = Team::default();
+        for id in 0..3 {
+            let id = SurvivorId::try_new(id).expect("0..3 is in team capacity");
+            let updates = [
+                SurvivorUpdate::perk()
+                    .id(id)
+                    .perk(PerkName::UpTheAnte)
+                    .tier(TierSlot::new(Some(Tier::Three)))
+                    .call(),
+                SurvivorUpdate::perk()
+                    .id(id)
+                    .perk(PerkName::SlipperyMeat)
+                    .tier(TierSlot::new(Some(Tier::Three)))
+                    .call(),
+            ];
+            for update in updates {
+                team.alter(update)
+                    .expect("Default team has free perk slots.");
+            }
+        }
+
+        assert!(agrees_with_analytic(&team, 50_000, 7))
+    }
+
+    #[test]
+    fn lock_and_dead_owner_agree_with_luck_output() {
+        let id = |x| SurvivorId::try_new(x).expect("0..3 is in team capacity");
+        let mut team = Team::default();
+        let updates = [
+            SurvivorUpdate::perk()
+                .id(id(0))
+                .perk(PerkName::Deliverance)
+                .tier(TierSlot::new(Some(Tier::One)))
+                .call(),
+            SurvivorUpdate::lock_condition().id(id(0)).met(true).call(),
+            SurvivorUpdate::perk()
+                .id(id(1))
+                .perk(PerkName::UpTheAnte)
+                .tier(TierSlot::new(Some(Tier::Three)))
+                .call(),
+            SurvivorUpdate::living_status()
+                .id(id(1))
+                .alive(false)
+                .call(),
+            SurvivorUpdate::perk()
+                .id(id(2))
+                .perk(PerkName::UpTheAnte)
+                .tier(TierSlot::new(Some(Tier::Two)))
+                .call(),
+            SurvivorUpdate::living_status()
+                .id(id(3))
+                .alive(false)
+                .call(),
+        ];
+        for update in updates {
+            team.alter(update)
+                .expect("Default team has free perk slots.");
+        }
+
+        assert!(agrees_with_analytic(&team, 50_000, 11))
+    }
+
+    #[test]
+    fn same_seed_same_rolls() {
+        let team = Team::default();
+        let rules = Ruleset::default();
+
+        assert_eq!(
+            team.simulate(&rules, 1_000, 42),
+            team.simulate(&rules, 1_000, 42)
+        )
+    }
+
+    #[test]
+    fn interval_contains_rate() {
+        let rate = EscapeRate {
+            escapes: 30,
+            trials: 100,
+        };
+        let (low, high) = rate.confidence_interval(Z_95);
+
+        assert!(low < 0.3 && 0.3 < high);
+        assert!(0.2 < low && high < 0.4)
+    }
+}
@@ -1,36 +1,94 @@
+use num_traits::ToPrimitive;
+use nutype::nutype;
+
 use super::{
     constants::misc as k,
+    distribution::EscapeDistribution,
     living_count::{LivingCount, LivingCountError},
-    luck_record::{PlayerTeamConverter, TeamLuckRecord},
+    luck_record::{Luck, PlayerTeamConverter, Probability, TeamLuckRecord},
+    patch::{self, GameVersion, PatchError},
     player::Player,
-    update::{SurvivorId, SurvivorUpdate},
+    provenance::LuckBreakdown,
+    ruleset::Ruleset,
+    update::{SurvivorId, SurvivorUpdate, UpdateError},
 };
 
+#[cfg(feature = "serde")]
+use super::team_form::TeamForm;
+#[cfg(feature = "serde")]
+pub use super::team_form::TeamFormError;
+
+/// The number of survivors in a match, from a lone survivor up to the
+/// eight of 2v8.
+#[nutype(
+    validate(greater_or_equal = 1, less_or_equal = k::TEAM_MAX_CAPACITY),
+    derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, AsRef, Deref, Display),
+    default = k::DEFAULT_TEAM_SIZE
+)]
+pub struct TeamSize(usize);
+
+/// The survivors of a match. Only the first `size` players take part;
+/// the rest are kept at their defaults so that equal teams compare equal.
+///
+/// With the `serde` feature a team is written as a list of survivors, and
+/// reading one back applies every item as an update, so a file can never
+/// hold a team which `alter` would refuse.
 #[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
-pub struct Team([Player; k::TEAM_MAX_CAPACITY]);
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "TeamForm", try_from = "TeamForm")
+)]
+pub struct Team {
+    players: [Player; k::TEAM_MAX_CAPACITY],
+    size: TeamSize,
+}
 
 // Accessor Methods
 impl Team {
+    pub fn with_size(size: TeamSize) -> Self {
+        Self {
+            size,
+            ..Self::default()
+        }
+    }
+    pub fn size(&self) -> TeamSize {
+        self.size
+    }
     pub fn list(&self) -> impl Iterator<Item = &Player> + '_ {
-        self.0.iter()
+        self.players[..*self.size].iter()
+    }
+    pub fn ids(&self) -> impl Iterator<Item = SurvivorId> + use<> {
+        (0..*self.size)
+            .map(|i| SurvivorId::try_new(i).expect("Team size is at most TEAM_MAX_CAPACITY."))
+    }
+    pub fn contains(&self, i: SurvivorId) -> bool {
+        *i < *self.size
     }
+    /// # Panics
+    /// If the survivor is not in the team, which `contains` checks.
     pub fn get_player(&self, i: SurvivorId) -> &Player {
-        self.0
-            .get(*i)
-            .expect("SurvivorId always valid for Team size.")
+        self.list()
+            .nth(*i)
+            .expect("SurvivorId must be within the team size.")
     }
 }
 
 // Mutating Methods
 impl Team {
-    fn get_player_mut(&mut self, i: SurvivorId) -> &mut Player {
-        self.0
-            .get_mut(*i)
-            .expect("SurvivorId always valid for Team size.")
+    /// Applies an update to one survivor. A refused update leaves the team unchanged.
+    pub fn alter(&mut self, update: SurvivorUpdate) -> Result<(), UpdateError> {
+        let id = *update.id();
+        if !self.contains(id) {
+            return Err(UpdateError::NotInTeam(id));
+        }
+        self.players[*id].alter(*update.update())
     }
-    pub fn alter(&mut self, update: SurvivorUpdate) {
-        let player_to_change = self.get_player_mut(*update.id());
-        player_to_change.alter(*update.update());
+    /// Changes the number of survivors. Survivors who stay keep their
+    /// loadouts, while those who leave are forgotten.
+    pub fn resize(&mut self, size: TeamSize) {
+        self.players[*size..].fill(Player::default());
+        self.size = size;
     }
 }
 
@@ -49,29 +107,88 @@ impl Team {
             .expect("Cannot generate living count above the max from a list of size max.")
     }
 
-    fn make_team_luck_records(&self) -> impl Iterator<Item = TeamLuckRecord> + '_ {
+    fn make_team_luck_records<'a>(
+        &'a self,
+        rules: &'a Ruleset,
+    ) -> impl Iterator<Item = TeamLuckRecord> + 'a {
         self.list().enumerate().map(|(id, player)| {
-            let player_luck = player.make_player_luck();
+            let player_luck = player.make_player_luck(rules);
             let converter = PlayerTeamConverter::new(self.alive_not_counting(&id));
             converter.convert(&player_luck)
         })
     }
 
-    fn collate_luck(&self) -> TeamLuckRecord {
-        let base_luck: TeamLuckRecord = TeamLuckRecord::with_global(k::BASE_UNHOOK_CHANCE);
-        let team_luck_records = self.make_team_luck_records();
+    fn make_traced_team_luck_records<'a>(
+        &'a self,
+        rules: &'a Ruleset,
+    ) -> impl Iterator<Item = TeamLuckRecord> + 'a {
+        self.list().enumerate().map(|(id, player)| {
+            let player_luck = player.make_player_luck_traced(rules);
+            let owner = SurvivorId::try_new(id).expect("Team size is at most TEAM_MAX_CAPACITY.");
+            let converter = PlayerTeamConverter::new(self.alive_not_counting(&id)).owned_by(owner);
+            converter.convert_traced(&player_luck)
+        })
+    }
+
+    pub(crate) fn collate_luck(&self, rules: &Ruleset) -> TeamLuckRecord {
+        let base_luck: TeamLuckRecord = TeamLuckRecord::with_global(*rules.base_unhook_chance());
+        let team_luck_records = self.make_team_luck_records(rules);
 
         team_luck_records.fold(base_luck, |acc, x| &acc + &x)
     }
 
-    pub fn luck_output(&self) -> Vec<(f64, f64)> {
-        let mut output: Vec<(f64, f64)> = Vec::with_capacity(k::TEAM_MAX_CAPACITY);
+    /// As `collate_luck`, with where each survivor's luck came from. The
+    /// sources are only gathered here, so that the odds, which are worked
+    /// out far more often, are not slowed by them.
+    fn collate_luck_traced(&self, rules: &Ruleset) -> TeamLuckRecord {
+        let base_luck: TeamLuckRecord = TeamLuckRecord::with_base(*rules.base_unhook_chance());
+        let team_luck_records = self.make_traced_team_luck_records(rules);
+
+        team_luck_records.fold(base_luck, |acc, x| &acc + &x)
+    }
 
-        self.collate_luck()
-            .make_single_and_total_unhook_pairs()
+    /// Each survivor's exact chance of escaping on one attempt and on any of their attempts
+    pub fn exact_luck_output(&self, rules: &Ruleset) -> Vec<(Luck, Probability)> {
+        self.collate_luck(rules)
+            .make_single_and_total_unhook_pairs(rules.base_unhook_attempts())
+            .collect()
+    }
+
+    /// Each survivor's chance of escaping on exactly, and by, each of their attempts
+    pub fn escape_distributions(&self, rules: &Ruleset) -> Vec<EscapeDistribution> {
+        self.collate_luck(rules)
+            .make_escape_distributions(rules.base_unhook_attempts())
+            .collect()
+    }
+
+    /// Where each survivor's luck on one attempt comes from, summing to
+    /// the luck of `exact_luck_output`
+    pub fn luck_breakdowns(&self, rules: &Ruleset) -> Vec<LuckBreakdown> {
+        self.collate_luck_traced(rules).luck_breakdowns().collect()
+    }
+
+    pub fn luck_output(&self, rules: &Ruleset) -> Vec<(f64, f64)> {
+        let mut output: Vec<(f64, f64)> = Vec::with_capacity(*self.size);
+
+        self.exact_luck_output(rules)
+            .into_iter()
+            .map(|(single, total)| (to_f64(&single), to_f64(&total)))
             .for_each(|x| output.push(x));
         output
     }
+
+    /// The luck output of this team under the ruleset in force during a past game version
+    pub fn luck_output_for_patch(
+        &self,
+        version: GameVersion,
+    ) -> Result<Vec<(f64, f64)>, PatchError> {
+        patch::ruleset_for(version).map(|rules| self.luck_output(&rules))
+    }
+}
+
+fn to_f64<T: ToPrimitive>(x: &T) -> f64 {
+    x.to_f64()
+        .expect("A probability is always within the range of f64.")
 }
 
 #[cfg(test)]
@@ -81,32 +198,39 @@ pub mod arb {
     use proptest::prelude::*;
 
     prop_compose! {
-        pub fn team()(players in prop::collection::vec(player::arb::player(), k::TEAM_MAX_CAPACITY)) -> Team {
-            let mut team = [Player::default(); k::TEAM_MAX_CAPACITY];
+        pub fn team_up_to(max: usize)(players in prop::collection::vec(player::arb::player(), 1..=max)) -> Team {
+            let size = TeamSize::try_new(players.len()).expect("Generated team size is at most max.");
+            let mut team = Team::with_size(size);
 
-            for (i, player) in players.into_iter().take(k::TEAM_MAX_CAPACITY).enumerate() {
-                team[i] = player;
+            for (i, player) in players.into_iter().enumerate() {
+                team.players[i] = player;
             }
 
-            Team(team)
+            team
         }
     }
 
+    pub fn team() -> impl Strategy<Value = Team> {
+        team_up_to(k::TEAM_MAX_CAPACITY)
+    }
+
     pub fn collate_luck_cfg_test(t: &Team) -> TeamLuckRecord {
-        t.collate_luck()
+        t.collate_luck(&Ruleset::default())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::constants::observations as obs;
+    use super::super::perk::{PerkName, Tier, TierSlot};
+    use super::super::provenance::{LuckContribution, LuckSource};
     use super::*;
     use proptest::prelude::*;
 
     proptest! {
         #[test]
         fn no_player_single_try_less_than_min_single_luck(team in arb::team()) {
-            let lucks = team.luck_output();
+            let lucks = team.luck_output(&Ruleset::default());
             let all_single_lucks: Vec<f64> = lucks.into_iter().map(|(single, _)| single).collect();
             let single_lucks_gte_min: Vec<bool> = all_single_lucks.into_iter().map(|luck| luck >= obs::MIN_SINGLE_LUCK).collect();
             let all_single_lucks_gte_min: bool = single_lucks_gte_min.into_iter().all(|x| x);
@@ -118,14 +242,189 @@ mod tests {
 
     proptest! {
         #[test]
-        fn no_player_single_try_more_than_max_single_luck(team in arb::team()) {
-            let lucks = team.luck_output();
+        fn no_player_single_try_more_than_max_single_luck(team in arb::team_up_to(k::DEFAULT_TEAM_SIZE)) {
+            let lucks = team.luck_output(&Ruleset::default());
             let all_single_lucks: Vec<f64> = lucks.into_iter().map(|(single, _)| single).collect();
-            let single_lucks_lte_min: Vec<bool> = all_single_lucks.into_iter().map(|luck| luck <= obs::MAX_SINGLE_LUCK).collect();
+            let single_lucks_lte_min: Vec<bool> = all_single_lucks.into_iter().map(|luck| luck <= obs::MAX_SINGLE_LUCK || luck == obs::LOCKED_SINGLE_LUCK).collect();
             let all_single_lucks_lte_min: bool = single_lucks_lte_min.into_iter().all(|x| x);
 
 
             prop_assert!(all_single_lucks_lte_min)
         }
     }
+
+    proptest! {
+        #[test]
+        fn distribution_total_matches_output(team in arb::team()) {
+            let rules = Ruleset::default();
+            let totals: Vec<Probability> = team
+                .escape_distributions(&rules)
+                .iter()
+                .map(EscapeDistribution::total)
+                .collect();
+            let expected: Vec<Probability> = team
+                .exact_luck_output(&rules)
+                .into_iter()
+                .map(|(_, total)| total)
+                .collect();
+
+            prop_assert_eq!(totals, expected)
+        }
+    }
+
+    #[test]
+    fn patch_before_up_the_ante_ignores_it() {
+        let mut team = Team::default();
+        let with_uta = SurvivorUpdate::perk()
+            .id(SurvivorId::try_new(0).expect("0 is in team capacity"))
+            .perk(PerkName::UpTheAnte)
+            .tier(TierSlot::new(Some(Tier::Three)))
+            .call();
+        let without_uta = team.luck_output(&Ruleset::default());
+        team.alter(with_uta)
+            .expect("Default team has free perk slots.");
+
+        assert_eq!(
+            team.luck_output_for_patch(GameVersion::new(1, 0, 0)),
+            Ok(without_uta.clone())
+        );
+        assert_ne!(
+            team.luck_output_for_patch(GameVersion::new(1, 4, 0)),
+            Ok(without_uta)
+        );
+    }
+
+    #[test]
+    fn patch_before_lock_perk_does_not_lock() {
+        let id = SurvivorId::try_new(0).expect("0 is in team capacity");
+        let locked_by = |perk, version| {
+            let mut team = Team::default();
+            team.alter(
+                SurvivorUpdate::perk()
+                    .id(id)
+                    .perk(perk)
+                    .tier(TierSlot::new(Some(Tier::One)))
+                    .call(),
+            )
+            .expect("Default team has free perk slots.");
+            team.alter(SurvivorUpdate::lock_condition().id(id).met(true).call())
+                .expect("Survivor 0 is in the team.");
+            let output = team
+                .luck_output_for_patch(version)
+                .expect("Every version since release has rules.");
+            output[0] == (1., 1.)
+        };
+
+        assert!(!locked_by(PerkName::Deliverance, GameVersion::new(2, 1, 0)));
+        assert!(locked_by(PerkName::Deliverance, GameVersion::new(2, 2, 0)));
+        assert!(!locked_by(PerkName::Wicked, GameVersion::new(7, 1, 0)));
+        assert!(locked_by(PerkName::Wicked, GameVersion::new(7, 2, 0)))
+    }
+
+    #[test]
+    fn exact_output_without_luck_items() {
+        let exact = Team::default().exact_luck_output(&Ruleset::default());
+        let base = super::super::constants::percent(4);
+        // 1 - (24/25)^3
+        let all_tries =
+            Probability::from_integer(1.into()) - Probability::new(24.into(), 25.into()).pow(3);
+
+        assert_eq!(exact, vec![(base, all_tries); k::DEFAULT_TEAM_SIZE])
+    }
+
+    proptest! {
+        #[test]
+        fn luck_never_exceeds_certainty(team in arb::team()) {
+            let lucks = team.luck_output(&Ruleset::default());
+
+            prop_assert_eq!(lucks.len(), *team.size());
+            prop_assert!(lucks.into_iter().all(|(single, total)| single <= 1. && total <= 1.))
+        }
+    }
+
+    #[test]
+    fn up_the_ante_scales_with_team_size() {
+        let size = TeamSize::try_new(k::TEAM_MAX_CAPACITY).expect("Capacity is a valid size.");
+        let mut team = Team::with_size(size);
+        team.alter(
+            SurvivorUpdate::perk()
+                .id(SurvivorId::try_new(0).expect("0 is in team capacity"))
+                .perk(PerkName::UpTheAnte)
+                .tier(TierSlot::new(Some(Tier::One)))
+                .call(),
+        )
+        .expect("Default team has free perk slots.");
+        let (single, _) = team.exact_luck_output(&Ruleset::default())[1];
+
+        // 4% base, plus 1% for each of the seven other survivors
+        assert_eq!(single, super::super::constants::percent(11))
+    }
+
+    #[test]
+    fn resizing_forgets_departed_survivors() {
+        let mut team = Team::default();
+        let last =
+            SurvivorId::try_new(k::DEFAULT_TEAM_SIZE - 1).expect("Default size is a valid id.");
+        team.alter(SurvivorUpdate::living_status().id(last).alive(false).call())
+            .expect("Survivor is in the team.");
+
+        team.resize(TeamSize::try_new(1).expect("1 is a valid size."));
+        assert_eq!(
+            team.alter(SurvivorUpdate::living_status().id(last).alive(true).call()),
+            Err(UpdateError::NotInTeam(last))
+        );
+        team.resize(TeamSize::default());
+        assert_eq!(team, Team::default())
+    }
+
+    proptest! {
+        #[test]
+        fn breakdowns_sum_to_luck(team in arb::team()) {
+            let rules = Ruleset::default();
+            let breakdowns = team.luck_breakdowns(&rules);
+            let totals: Vec<Luck> = breakdowns.iter().map(LuckBreakdown::total).collect();
+            let expected: Vec<Luck> = team
+                .exact_luck_output(&rules)
+                .into_iter()
+                .map(|(single, _)| single)
+                .collect();
+
+            prop_assert_eq!(totals, expected);
+            // Every record of a real team is made from items
+            prop_assert!(breakdowns
+                .iter()
+                .flat_map(LuckBreakdown::contributions)
+                .all(|x| *x.source() != LuckSource::Untraced))
+        }
+    }
+
+    #[test]
+    fn up_the_ante_traced_to_owner() {
+        let owner = SurvivorId::try_new(1).expect("1 is in team capacity");
+        let mut team = Team::default();
+        team.alter(
+            SurvivorUpdate::perk()
+                .id(owner)
+                .perk(PerkName::UpTheAnte)
+                .tier(TierSlot::new(Some(Tier::Three)))
+                .call(),
+        )
+        .expect("Default team has free perk slots.");
+        let breakdown = &team.luck_breakdowns(&Ruleset::default())[0];
+
+        let up_the_ante = LuckContribution::new(
+            LuckSource::UpTheAnte {
+                owner: Some(owner),
+                living_others: 3,
+            },
+            super::super::constants::perk_luck::UTA_TIER3 * 3,
+        );
+        assert_eq!(
+            breakdown.contributions(),
+            &vec![
+                LuckContribution::new(LuckSource::Base, *Ruleset::default().base_unhook_chance()),
+                up_the_ante
+            ]
+        )
+    }
 }
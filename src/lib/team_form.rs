@@ -0,0 +1,216 @@
+use derive_more::Display;
+
+use super::{
+    constants::misc as k,
+    offering::{Offering, OfferingSlot},
+    perk::{Perk, PerkSlot, PerkSlotId},
+    roster::{NamedTeam, Roster, SurvivorLabel},
+    team::{Team, TeamSize},
+    update::{SurvivorUpdate, UpdateError},
+};
+
+/// The serialized shape of a `Team` and of a `NamedTeam`
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TeamForm {
+    survivors: Vec<SurvivorForm>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SurvivorForm {
+    /// Only written for a named team, and ignored when read as a team
+    #[serde(skip_serializing_if = "String::is_empty")]
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    character: Option<String>,
+    perks: [Option<Perk>; k::MAX_PERKS],
+    offering: Option<Offering>,
+    alive: bool,
+    lock_condition_met: bool,
+}
+
+impl Default for SurvivorForm {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            character: None,
+            perks: [None; k::MAX_PERKS],
+            offering: None,
+            alive: true,
+            lock_condition_met: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum TeamFormError {
+    #[display(
+        "a team must have from 1 to {} survivors, not {_0}",
+        k::TEAM_MAX_CAPACITY
+    )]
+    Size(usize),
+    #[display("{_0}")]
+    Update(UpdateError),
+}
+
+impl std::error::Error for TeamFormError {}
+
+impl From<Team> for TeamForm {
+    fn from(team: Team) -> Self {
+        let survivors = team
+            .list()
+            .map(|player| SurvivorForm {
+                name: String::new(),
+                character: None,
+                perks: std::array::from_fn(|slot| {
+                    let slot = PerkSlotId::try_new(slot)
+                        .expect("Array of MAX_PERKS indexes below MAX_PERKS.");
+                    player.perk_slot(slot).into_inner()
+                }),
+                offering: player.offering().into_inner(),
+                alive: player.is_alive(),
+                lock_condition_met: player.lock_condition_met(),
+            })
+            .collect();
+        Self { survivors }
+    }
+}
+
+impl TryFrom<TeamForm> for Team {
+    type Error = TeamFormError;
+
+    fn try_from(form: TeamForm) -> Result<Self, Self::Error> {
+        let size = TeamSize::try_new(form.survivors.len())
+            .map_err(|_| TeamFormError::Size(form.survivors.len()))?;
+        let mut team = Team::with_size(size);
+
+        for (id, survivor) in team.ids().zip(form.survivors) {
+            let perks = survivor.perks.into_iter().enumerate().map(|(slot, perk)| {
+                let slot =
+                    PerkSlotId::try_new(slot).expect("Array of MAX_PERKS indexes below MAX_PERKS.");
+                SurvivorUpdate::perk_slot()
+                    .id(id)
+                    .slot(slot)
+                    .perk(PerkSlot::new(perk))
+                    .call()
+            });
+            let rest = [
+                SurvivorUpdate::offering()
+                    .id(id)
+                    .offering(OfferingSlot::new(survivor.offering))
+                    .call(),
+                SurvivorUpdate::living_status()
+                    .id(id)
+                    .alive(survivor.alive)
+                    .call(),
+                SurvivorUpdate::lock_condition()
+                    .id(id)
+                    .met(survivor.lock_condition_met)
+                    .call(),
+            ];
+            for update in perks.chain(rest) {
+                team.alter(update).map_err(TeamFormError::Update)?;
+            }
+        }
+        Ok(team)
+    }
+}
+
+impl From<NamedTeam> for TeamForm {
+    fn from(named: NamedTeam) -> Self {
+        let mut form = TeamForm::from(named.team);
+        for (id, survivor) in named.team.ids().zip(&mut form.survivors) {
+            let label = named.roster.get(id);
+            survivor.name.clone_from(label.name());
+            survivor.character.clone_from(label.character());
+        }
+        form
+    }
+}
+
+impl TryFrom<TeamForm> for NamedTeam {
+    type Error = TeamFormError;
+
+    fn try_from(form: TeamForm) -> Result<Self, Self::Error> {
+        let labels: Vec<_> = form
+            .survivors
+            .iter()
+            .map(|x| SurvivorLabel::new(&x.name, x.character.as_deref()))
+            .collect();
+        let team = Team::try_from(form)?;
+        let mut roster = Roster::default();
+        for (id, label) in team.ids().zip(labels) {
+            *roster.get_mut(id) = label;
+        }
+        Ok(NamedTeam::new(team, roster))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{perk::PerkName, roster, team};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn team_round_trips_through_json(team in team::arb::team()) {
+            let text = serde_json::to_string(&team).expect("Teams serialize.");
+
+            prop_assert_eq!(serde_json::from_str::<Team>(&text).ok(), Some(team))
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn named_team_round_trips_through_json(named in roster::arb::named_team()) {
+            let text = serde_json::to_string(&named).expect("Named teams serialize.");
+
+            prop_assert_eq!(serde_json::from_str::<NamedTeam>(&text).ok(), Some(named.clone()));
+            prop_assert_eq!(serde_json::from_str::<Team>(&text).ok(), Some(named.team))
+        }
+    }
+
+    #[test]
+    fn plain_team_reads_as_unnamed() {
+        let text = serde_json::to_string(&Team::default()).expect("Teams serialize.");
+
+        assert!(!text.contains("name"));
+        assert_eq!(
+            serde_json::from_str::<NamedTeam>(&text).ok(),
+            Some(NamedTeam::default())
+        )
+    }
+
+    #[test]
+    fn missing_fields_keep_defaults() {
+        let team: Team = serde_json::from_str(
+            r#"{ "survivors": [
+                { "perks": [{ "name": "up-the-ante", "tier": "three" }, null, null, null] },
+                {}, {}, {}
+            ] }"#,
+        )
+        .expect("Partial survivors are valid.");
+        let first = team.get_player(team.ids().next().expect("Team has survivors."));
+
+        assert_eq!(team.size(), TeamSize::default());
+        assert!(first.is_alive());
+        assert_eq!(
+            first.get_perk_tier(PerkName::UpTheAnte),
+            Some(&crate::perk::Tier::Three)
+        )
+    }
+
+    #[test]
+    fn invalid_teams_refused() {
+        let duplicate = r#"{ "survivors": [{ "perks": [
+            { "name": "slippery-meat", "tier": "one" },
+            { "name": "slippery-meat", "tier": "two" },
+            null, null
+        ] }] }"#;
+
+        assert!(serde_json::from_str::<Team>(r#"{ "survivors": [] }"#).is_err());
+        assert!(serde_json::from_str::<Team>(duplicate).is_err());
+    }
+}
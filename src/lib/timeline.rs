@@ -0,0 +1,206 @@
+use derive_more::Display;
+use num_traits::One;
+
+use super::{
+    luck_record::{Luck, Probability, exact_probability},
+    ruleset::Ruleset,
+    team::Team,
+    update::{SurvivorId, SurvivorUpdate},
+};
+
+/// Something which happens during a match and may change the luck of
+/// later self-unhook attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineEvent {
+    /// A survivor attempts to unhook themself
+    Attempt(SurvivorId),
+    TeammateDies(SurvivorId),
+    /// A survivor leaves through an exit. For luck this is the same as
+    /// dying: they no longer count towards anyone's Up the Ante.
+    TeammateEscapes(SurvivorId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum TimelineError {
+    #[display("survivor {} has already left the trial", **_0 + 1)]
+    NotInTrial(SurvivorId),
+    #[display("survivor {} has no unhook attempts left", **_0 + 1)]
+    NoAttemptsLeft(SurvivorId),
+}
+
+impl std::error::Error for TimelineError {}
+
+/// The luck of every attempt made in a timeline, in the order they were made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineOutcome {
+    attempts: Vec<Vec<Luck>>,
+    team: Team,
+}
+
+impl TimelineOutcome {
+    /// The luck of each attempt made by a survivor
+    ///
+    /// # Panics
+    /// If the survivor is not in the team.
+    pub fn attempt_lucks(&self, id: SurvivorId) -> &[Luck] {
+        &self.attempts[*id]
+    }
+    /// The chance that any of a survivor's attempts succeeded, which
+    /// is `1 - Π(1 - l_i)` over the luck of each of their attempts.
+    pub fn escape_chance(&self, id: SurvivorId) -> Probability {
+        let chance_fail_all: Probability = self.attempts[*id]
+            .iter()
+            .map(|luck| Probability::one() - exact_probability(*luck))
+            .product();
+        Probability::one() - chance_fail_all
+    }
+    pub fn escape_chances(&self) -> Vec<Probability> {
+        self.team.ids().map(|id| self.escape_chance(id)).collect()
+    }
+    /// The team as it stands after every event
+    pub fn team(&self) -> &Team {
+        &self.team
+    }
+}
+
+impl Team {
+    /// Plays a sequence of events over the team, taking the luck of each
+    /// attempt from the team as it stands when the attempt is made.
+    pub fn run_timeline(
+        &self,
+        rules: &Ruleset,
+        events: impl IntoIterator<Item = TimelineEvent>,
+    ) -> Result<TimelineOutcome, TimelineError> {
+        let mut outcome = TimelineOutcome {
+            attempts: vec![Vec::new(); *self.size()],
+            team: *self,
+        };
+
+        for event in events {
+            match event {
+                TimelineEvent::Attempt(id) => {
+                    let team = &outcome.team;
+                    if !team.contains(id) || team.get_player(id).is_dead() {
+                        return Err(TimelineError::NotInTrial(id));
+                    }
+                    let max_attempts = team.escape_distributions(rules)[*id].attempts();
+                    if outcome.attempts[*id].len() >= max_attempts {
+                        return Err(TimelineError::NoAttemptsLeft(id));
+                    }
+                    let (luck, _) = team.exact_luck_output(rules)[*id];
+                    outcome.attempts[*id].push(luck);
+                }
+                TimelineEvent::TeammateDies(id) | TimelineEvent::TeammateEscapes(id) => {
+                    let team = &outcome.team;
+                    if !team.contains(id) || team.get_player(id).is_dead() {
+                        return Err(TimelineError::NotInTrial(id));
+                    }
+                    outcome
+                        .team
+                        .alter(SurvivorUpdate::living_status().id(id).alive(false).call())
+                        .expect("Changing living status is never refused.");
+                }
+            }
+        }
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::{misc as k, percent},
+        perk::{PerkName, Tier, TierSlot},
+        team,
+    };
+    use proptest::prelude::*;
+
+    fn id(x: usize) -> SurvivorId {
+        SurvivorId::try_new(x).expect("Test ids are in team capacity.")
+    }
+
+    fn uta_team() -> Team {
+        let mut team = Team::default();
+        team.alter(
+            SurvivorUpdate::perk()
+                .id(id(0))
+                .perk(PerkName::UpTheAnte)
+                .tier(TierSlot::new(Some(Tier::Three)))
+                .call(),
+        )
+        .expect("Default team has free perk slots.");
+        team
+    }
+
+    proptest! {
+        #[test]
+        fn uninterrupted_attempts_match_luck_output(team in team::arb::team(), x in 0..k::TEAM_MAX_CAPACITY) {
+            prop_assume!(x < *team.size());
+            let rules = Ruleset::default();
+            let events = (0..team.escape_distributions(&rules)[x].attempts())
+                .map(|_| TimelineEvent::Attempt(id(x)));
+            let outcome = team.run_timeline(&rules, events);
+            let expected = team.exact_luck_output(&rules)[x].1.clone();
+
+            match team.get_player(id(x)).is_dead() {
+                true => prop_assert_eq!(outcome.err(), Some(TimelineError::NotInTrial(id(x)))),
+                false => prop_assert_eq!(outcome.map(|o| o.escape_chance(id(x))), Ok(expected)),
+            }
+        }
+    }
+
+    #[test]
+    fn deaths_between_attempts_lower_later_luck() {
+        let events = [
+            TimelineEvent::Attempt(id(0)),
+            TimelineEvent::TeammateDies(id(1)),
+            TimelineEvent::Attempt(id(0)),
+            TimelineEvent::TeammateEscapes(id(2)),
+            TimelineEvent::Attempt(id(0)),
+        ];
+        let outcome = uta_team()
+            .run_timeline(&Ruleset::default(), events)
+            .expect("Survivor 0 has three attempts.");
+        let p = |x: i64| exact_probability(percent(x));
+
+        // 4% base, plus 3% from Up the Ante for each other survivor still in the trial
+        assert_eq!(
+            outcome.attempt_lucks(id(0)),
+            &[percent(13), percent(10), percent(7)]
+        );
+        assert_eq!(
+            outcome.escape_chance(id(0)),
+            Probability::one() - (p(100) - p(13)) * (p(100) - p(10)) * (p(100) - p(7))
+        );
+        assert_eq!(
+            outcome.escape_chance(id(1)),
+            Probability::from_integer(0.into())
+        )
+    }
+
+    #[test]
+    fn departed_survivors_refused() {
+        let rules = Ruleset::default();
+        let events = [
+            TimelineEvent::TeammateDies(id(1)),
+            TimelineEvent::Attempt(id(1)),
+        ];
+
+        assert_eq!(
+            Team::default().run_timeline(&rules, events),
+            Err(TimelineError::NotInTrial(id(1)))
+        );
+    }
+
+    #[test]
+    fn attempts_are_limited() {
+        let rules = Ruleset::default();
+        let events = [TimelineEvent::Attempt(id(0)); 4];
+
+        assert_eq!(
+            Team::default().run_timeline(&rules, events),
+            Err(TimelineError::NoAttemptsLeft(id(0)))
+        );
+    }
+}
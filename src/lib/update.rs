@@ -1,15 +1,28 @@
 use bon::bon;
 use derive_getters::Getters;
+use derive_more::Display;
 use nutype::nutype;
 
 use super::{
     offering,
     offering::OfferingSlot,
-    perk::{PerkName, TierSlot},
+    perk::{PerkName, PerkSlot, PerkSlotId, TierSlot},
 };
 use crate::constants::misc as k;
 
+#[cfg(feature = "serde")]
+pub use super::update_form::UpdateFormError;
+#[cfg(feature = "serde")]
+use super::update_form::{DataForm, UpdateForm};
+
+/// With the `serde` feature an update is written as an object tagged
+/// by its `kind`, such as `{ "kind": "alive", "survivor": 0, "alive": false }`.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Getters)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "UpdateForm", try_from = "UpdateForm")
+)]
 pub struct SurvivorUpdate {
     id: SurvivorId,
     update: SurvivorUpdateData,
@@ -20,10 +33,9 @@ impl SurvivorUpdate {
     fn from_perk(id: SurvivorId, perk: PerkName, tier: TierSlot) -> Self {
         Self {
             id,
-            update: SurvivorUpdateData::LoadoutUpdate(LoadoutUpdate::Perk(PerkUpdate {
-                perk,
-                value: tier,
-            })),
+            update: SurvivorUpdateData::LoadoutUpdate(LoadoutUpdate::Perk(PerkUpdate::new(
+                perk, tier,
+            ))),
         }
     }
     #[builder]
@@ -35,6 +47,15 @@ impl SurvivorUpdate {
         let id = SurvivorId::try_new(id)?;
         Ok(Self::from_perk(id, perk, tier))
     }
+    #[builder]
+    pub fn perk_slot(id: SurvivorId, slot: PerkSlotId, perk: PerkSlot) -> Self {
+        Self {
+            id,
+            update: SurvivorUpdateData::LoadoutUpdate(LoadoutUpdate::PerkSlot(
+                PerkSlotUpdate::new(slot, perk),
+            )),
+        }
+    }
     fn from_offering(id: SurvivorId, offering: OfferingSlot) -> Self {
         Self {
             id,
@@ -60,6 +81,13 @@ impl SurvivorUpdate {
             update: SurvivorUpdateData::Life(alive),
         }
     }
+    #[builder]
+    pub fn lock_condition(id: SurvivorId, met: bool) -> Self {
+        Self {
+            id,
+            update: SurvivorUpdateData::LockCondition(met),
+        }
+    }
     fn from_data(id: SurvivorId, update: SurvivorUpdateData) -> Self {
         Self { id, update }
     }
@@ -80,15 +108,26 @@ impl SurvivorUpdate {
 )]
 pub struct SurvivorId(usize);
 
+/// With the `serde` feature, update data is written as an update is,
+/// without the survivor.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "DataForm", try_from = "DataForm")
+)]
 pub enum SurvivorUpdateData {
     LoadoutUpdate(LoadoutUpdate),
     Life(bool),
+    LockCondition(bool),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LoadoutUpdate {
+    /// Equips, re-tiers or removes a perk wherever it sits in the loadout
     Perk(PerkUpdate),
+    /// Replaces the contents of a single perk slot
+    PerkSlot(PerkSlotUpdate),
     Offering(OfferingSlot),
 }
 
@@ -98,6 +137,38 @@ pub struct PerkUpdate {
     value: TierSlot,
 }
 
+impl PerkUpdate {
+    pub const fn new(perk: PerkName, value: TierSlot) -> Self {
+        Self { perk, value }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Getters)]
+pub struct PerkSlotUpdate {
+    slot: PerkSlotId,
+    value: PerkSlot,
+}
+
+impl PerkSlotUpdate {
+    pub const fn new(slot: PerkSlotId, value: PerkSlot) -> Self {
+        Self { slot, value }
+    }
+}
+
+/// The reasons an update may be refused. A refused update
+/// leaves whatever it was applied to unchanged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display)]
+pub enum UpdateError {
+    #[display("{_0:?} is already equipped in another perk slot")]
+    DuplicatePerk(PerkName),
+    #[display("every perk slot is already occupied")]
+    NoFreePerkSlot,
+    #[display("player {} is not in the team", **_0 + 1)]
+    NotInTeam(SurvivorId),
+}
+
+impl std::error::Error for UpdateError {}
+
 #[cfg(test)]
 pub mod arb {
     use super::*;
@@ -111,7 +182,10 @@ pub mod arb {
             offering in offering::arb::offering_slot_strategy(),
             perk_name in perk::arb::name(),
             perk_tier_slot in perk::arb::tier_slot(),
-            choice in 0..2
+            lock_condition_met in any::<bool>(),
+            perk_slot_id in 0..k::MAX_PERKS,
+            perk_slot in perk::arb::perk_slot(),
+            choice in 0..5
         ) -> SurvivorUpdate {
             let id: SurvivorId = SurvivorId::try_new(id).expect("choice should be in team capacity");
             
@@ -119,6 +193,11 @@ pub mod arb {
                 0 => SurvivorUpdate::living_status().id(id).alive(is_alive != 0).call(),
                 1 => SurvivorUpdate::offering().id(id).offering(offering).call(),
                 2 => SurvivorUpdate::perk().id(id).perk(perk_name).tier(perk_tier_slot).call(),
+                3 => SurvivorUpdate::lock_condition().id(id).met(lock_condition_met).call(),
+                4 => {
+                    let slot = PerkSlotId::try_new(perk_slot_id).expect("choice should be in perk capacity");
+                    SurvivorUpdate::perk_slot().id(id).slot(slot).perk(perk_slot).call()
+                }
                 _ => unreachable!()
             }
         }
@@ -136,6 +215,9 @@ mod tests {
     /// is used to prove that Team::alter works, Team::alter may
     /// not be used in this function.
     fn is_noop(su: SurvivorUpdate, t: team::Team) -> bool {
+        if !t.contains(su.id) {
+            return false;
+        }
         let survivor = t.get_player(su.id);
 
         match su.update {
@@ -147,7 +229,11 @@ mod tests {
                 let current_tier: Option<perk::Tier> = survivor.get_perk_tier(*p.perk()).copied();
                 current_tier == update_tier
             },
+            SurvivorUpdateData::LoadoutUpdate(LoadoutUpdate::PerkSlot(p)) => {
+                survivor.perk_slot(*p.slot()) == p.value()
+            },
             SurvivorUpdateData::Life(is_alive) => survivor.is_alive() == is_alive,
+            SurvivorUpdateData::LockCondition(met) => survivor.lock_condition_met() == met,
         }
     }
 
@@ -158,8 +244,11 @@ mod tests {
             mut team in team::arb::team()
         ) {
             prop_assume! { !is_noop(update, team) };
-            team.alter(update);
-            prop_assert!(is_noop(update, team))
+            let before = team;
+            match team.alter(update) {
+                Ok(()) => prop_assert!(is_noop(update, team)),
+                Err(_) => prop_assert_eq!(before, team),
+            }
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,216 @@
+use derive_more::Display;
+
+use super::{
+    offering::{Offering, OfferingSlot},
+    perk::{Perk, PerkName, PerkSlot, PerkSlotId, Tier, TierSlot},
+    update::{
+        LoadoutUpdate, PerkSlotUpdate, PerkUpdate, SurvivorId, SurvivorUpdate, SurvivorUpdateData,
+    },
+};
+
+/// The serialized shape of a `SurvivorUpdate`, tagged by the kind of
+/// change. Survivors and perk slots are counted from zero.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum UpdateForm {
+    Perk {
+        survivor: usize,
+        perk: PerkName,
+        tier: Option<Tier>,
+    },
+    PerkSlot {
+        survivor: usize,
+        slot: usize,
+        perk: Option<Perk>,
+    },
+    Offering {
+        survivor: usize,
+        offering: Option<Offering>,
+    },
+    Alive {
+        survivor: usize,
+        alive: bool,
+    },
+    LockCondition {
+        survivor: usize,
+        met: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum UpdateFormError {
+    #[display("there is no survivor {_0} in any team")]
+    Survivor(usize),
+    #[display("there is no perk slot {_0}")]
+    Slot(usize),
+}
+
+impl std::error::Error for UpdateFormError {}
+
+/// The serialized shape of a `SurvivorUpdateData`, which is that of an
+/// `UpdateForm` without the survivor.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum DataForm {
+    Perk { perk: PerkName, tier: Option<Tier> },
+    PerkSlot { slot: usize, perk: Option<Perk> },
+    Offering { offering: Option<Offering> },
+    Alive { alive: bool },
+    LockCondition { met: bool },
+}
+
+impl UpdateForm {
+    fn join(survivor: usize, data: DataForm) -> Self {
+        match data {
+            DataForm::Perk { perk, tier } => UpdateForm::Perk {
+                survivor,
+                perk,
+                tier,
+            },
+            DataForm::PerkSlot { slot, perk } => UpdateForm::PerkSlot {
+                survivor,
+                slot,
+                perk,
+            },
+            DataForm::Offering { offering } => UpdateForm::Offering { survivor, offering },
+            DataForm::Alive { alive } => UpdateForm::Alive { survivor, alive },
+            DataForm::LockCondition { met } => UpdateForm::LockCondition { survivor, met },
+        }
+    }
+
+    fn split(self) -> (usize, DataForm) {
+        match self {
+            UpdateForm::Perk {
+                survivor,
+                perk,
+                tier,
+            } => (survivor, DataForm::Perk { perk, tier }),
+            UpdateForm::PerkSlot {
+                survivor,
+                slot,
+                perk,
+            } => (survivor, DataForm::PerkSlot { slot, perk }),
+            UpdateForm::Offering { survivor, offering } => {
+                (survivor, DataForm::Offering { offering })
+            }
+            UpdateForm::Alive { survivor, alive } => (survivor, DataForm::Alive { alive }),
+            UpdateForm::LockCondition { survivor, met } => {
+                (survivor, DataForm::LockCondition { met })
+            }
+        }
+    }
+}
+
+impl From<SurvivorUpdateData> for DataForm {
+    fn from(data: SurvivorUpdateData) -> Self {
+        match data {
+            SurvivorUpdateData::LoadoutUpdate(LoadoutUpdate::Perk(x)) => DataForm::Perk {
+                perk: *x.perk(),
+                tier: x.value().into_inner(),
+            },
+            SurvivorUpdateData::LoadoutUpdate(LoadoutUpdate::PerkSlot(x)) => DataForm::PerkSlot {
+                slot: **x.slot(),
+                perk: x.value().into_inner(),
+            },
+            SurvivorUpdateData::LoadoutUpdate(LoadoutUpdate::Offering(x)) => DataForm::Offering {
+                offering: x.into_inner(),
+            },
+            SurvivorUpdateData::Life(alive) => DataForm::Alive { alive },
+            SurvivorUpdateData::LockCondition(met) => DataForm::LockCondition { met },
+        }
+    }
+}
+
+impl TryFrom<DataForm> for SurvivorUpdateData {
+    type Error = UpdateFormError;
+
+    fn try_from(form: DataForm) -> Result<Self, Self::Error> {
+        let loadout = match form {
+            DataForm::Perk { perk, tier } => {
+                LoadoutUpdate::Perk(PerkUpdate::new(perk, TierSlot::new(tier)))
+            }
+            DataForm::PerkSlot { slot, perk } => LoadoutUpdate::PerkSlot(PerkSlotUpdate::new(
+                PerkSlotId::try_new(slot).map_err(|_| UpdateFormError::Slot(slot))?,
+                PerkSlot::new(perk),
+            )),
+            DataForm::Offering { offering } => LoadoutUpdate::Offering(OfferingSlot::new(offering)),
+            DataForm::Alive { alive } => return Ok(SurvivorUpdateData::Life(alive)),
+            DataForm::LockCondition { met } => return Ok(SurvivorUpdateData::LockCondition(met)),
+        };
+        Ok(SurvivorUpdateData::LoadoutUpdate(loadout))
+    }
+}
+
+impl From<SurvivorUpdate> for UpdateForm {
+    fn from(update: SurvivorUpdate) -> Self {
+        UpdateForm::join(**update.id(), DataForm::from(*update.update()))
+    }
+}
+
+impl TryFrom<UpdateForm> for SurvivorUpdate {
+    type Error = UpdateFormError;
+
+    fn try_from(form: UpdateForm) -> Result<Self, Self::Error> {
+        let (survivor, data) = form.split();
+        let id = SurvivorId::try_new(survivor).map_err(|_| UpdateFormError::Survivor(survivor))?;
+        Ok(SurvivorUpdate::data()
+            .id(id)
+            .update(data.try_into()?)
+            .call())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::update;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn update_round_trips_through_json(update in update::arb::survivor_update_data()) {
+            let text = serde_json::to_string(&update).expect("Updates serialize.");
+
+            prop_assert_eq!(serde_json::from_str::<SurvivorUpdate>(&text).ok(), Some(update))
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn update_data_round_trips_through_json(update in update::arb::survivor_update_data()) {
+            let data = *update.update();
+            let text = serde_json::to_string(&data).expect("Update data serializes.");
+
+            prop_assert!(!text.contains("survivor"));
+            prop_assert_eq!(serde_json::from_str::<SurvivorUpdateData>(&text).ok(), Some(data))
+        }
+    }
+
+    #[test]
+    fn updates_read_from_tagged_json() {
+        let update: SurvivorUpdate = serde_json::from_str(
+            r#"{ "kind": "perk", "survivor": 1, "perk": "slippery-meat", "tier": "three" }"#,
+        )
+        .expect("A perk update is valid.");
+
+        assert_eq!(
+            update,
+            SurvivorUpdate::perk()
+                .id(SurvivorId::try_new(1).expect("Test ids are in team capacity."))
+                .perk(PerkName::SlipperyMeat)
+                .tier(TierSlot::new(Some(Tier::Three)))
+                .call()
+        )
+    }
+
+    #[test]
+    fn invalid_updates_refused() {
+        let far_survivor = r#"{ "kind": "alive", "survivor": 8, "alive": false }"#;
+        let far_slot = r#"{ "kind": "perk-slot", "survivor": 0, "slot": 4, "perk": null }"#;
+        let unknown_field = r#"{ "kind": "alive", "survivor": 0, "alive": false, "x": 1 }"#;
+
+        assert!(serde_json::from_str::<SurvivorUpdate>(far_survivor).is_err());
+        assert!(serde_json::from_str::<SurvivorUpdate>(far_slot).is_err());
+        assert!(serde_json::from_str::<SurvivorUpdate>(unknown_field).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use serde::{
+    Deserialize,
+    de::{IntoDeserializer, value::Error as NameError},
+};
+use wasm_bindgen::prelude::*;
+
+use super::{
+    offering::OfferingSlot,
+    perk::{PerkName, Tier, TierSlot},
+    ruleset::Ruleset,
+    team::{Team, TeamSize},
+    update::{SurvivorId, SurvivorUpdate, UpdateFormError},
+};
+
+/// A team for JavaScript. Survivors are counted from zero, and perks and
+/// offerings are named as they are serialized, such as "slippery-meat"
+/// and "salty-lips". Odds are calculated under the live game's rules.
+#[wasm_bindgen(js_name = Team)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WasmTeam(Team);
+
+/// One survivor's chance of escaping on one attempt and on any attempt
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Odds {
+    pub single: f64,
+    pub total: f64,
+}
+
+#[wasm_bindgen(js_class = Team)]
+impl WasmTeam {
+    /// An empty team of one to eight survivors
+    #[wasm_bindgen(constructor)]
+    pub fn new(size: usize) -> Result<WasmTeam, JsError> {
+        let size = TeamSize::try_new(size)
+            .map_err(|_| JsError::new(&format!("a team has one to eight survivors, not {size}")))?;
+        Ok(Self(Team::with_size(size)))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        *self.0.size()
+    }
+
+    #[wasm_bindgen(js_name = fromCode)]
+    pub fn from_code(code: &str) -> Result<WasmTeam, JsError> {
+        Ok(Self(Team::from_code(code)?))
+    }
+
+    #[wasm_bindgen(js_name = toCode)]
+    pub fn to_code(&self) -> String {
+        self.0.to_code()
+    }
+
+    /// Reads a team written as in the preset file
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<WasmTeam, JsError> {
+        Ok(Self(serde_json::from_str(json)?))
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.0).expect("Teams always serialize.")
+    }
+
+    /// Equips a perk at tier 1, 2 or 3, or removes it when the tier is left out
+    #[wasm_bindgen(js_name = setPerk)]
+    pub fn set_perk(
+        &mut self,
+        survivor: usize,
+        perk: &str,
+        tier: Option<u8>,
+    ) -> Result<(), JsError> {
+        let tier = tier
+            .map(|x| match x {
+                1 => Ok(Tier::One),
+                2 => Ok(Tier::Two),
+                3 => Ok(Tier::Three),
+                _ => Err(JsError::new(&format!("a perk tier is 1, 2 or 3, not {x}"))),
+            })
+            .transpose()?;
+        self.apply(
+            SurvivorUpdate::perk()
+                .id(survivor_id(survivor)?)
+                .perk(by_name::<PerkName>(perk)?)
+                .tier(TierSlot::new(tier))
+                .call(),
+        )
+    }
+
+    /// Brings an offering, or none when it is left out
+    #[wasm_bindgen(js_name = setOffering)]
+    pub fn set_offering(
+        &mut self,
+        survivor: usize,
+        offering: Option<String>,
+    ) -> Result<(), JsError> {
+        let offering = offering.as_deref().map(by_name).transpose()?;
+        self.apply(
+            SurvivorUpdate::offering()
+                .id(survivor_id(survivor)?)
+                .offering(OfferingSlot::new(offering))
+                .call(),
+        )
+    }
+
+    #[wasm_bindgen(js_name = setAlive)]
+    pub fn set_alive(&mut self, survivor: usize, alive: bool) -> Result<(), JsError> {
+        self.apply(
+            SurvivorUpdate::living_status()
+                .id(survivor_id(survivor)?)
+                .alive(alive)
+                .call(),
+        )
+    }
+
+    #[wasm_bindgen(js_name = setLockCondition)]
+    pub fn set_lock_condition(&mut self, survivor: usize, met: bool) -> Result<(), JsError> {
+        self.apply(
+            SurvivorUpdate::lock_condition()
+                .id(survivor_id(survivor)?)
+                .met(met)
+                .call(),
+        )
+    }
+
+    /// Applies an update written as JSON, such as
+    /// `{ "kind": "alive", "survivor": 0, "alive": false }`
+    #[wasm_bindgen(js_name = applyUpdate)]
+    pub fn apply_update(&mut self, update: &str) -> Result<(), JsError> {
+        self.apply(serde_json::from_str(update)?)
+    }
+
+    /// Each survivor's odds, by survivor id
+    #[wasm_bindgen(js_name = luckOutput)]
+    pub fn luck_output(&self) -> Vec<Odds> {
+        self.0
+            .luck_output(&Ruleset::default())
+            .into_iter()
+            .map(|(single, total)| Odds { single, total })
+            .collect()
+    }
+}
+
+impl WasmTeam {
+    /// A refused update leaves the team unchanged and is thrown to JavaScript
+    fn apply(&mut self, update: SurvivorUpdate) -> Result<(), JsError> {
+        Ok(self.0.alter(update)?)
+    }
+}
+
+fn survivor_id(survivor: usize) -> Result<SurvivorId, UpdateFormError> {
+    SurvivorId::try_new(survivor).map_err(|_| UpdateFormError::Survivor(survivor))
+}
+
+/// Reads an item by the kebab-case name it is serialized under
+fn by_name<'de, T: Deserialize<'de>>(name: &'de str) -> Result<T, NameError> {
+    T::deserialize(name.into_deserializer())
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn setters_match_the_library() {
+        let mut team = WasmTeam::new(2).expect("Two is a team size.");
+        team.set_perk(0, "up-the-ante", Some(3))
+            .expect("An empty survivor may equip a perk.");
+        team.set_offering(1, Some("salty-lips".into()))
+            .expect("An empty survivor may bring an offering.");
+
+        let mut expected = Team::with_size(TeamSize::try_new(2).expect("Two is a team size."));
+        for update in [
+            r#"{ "kind": "perk", "survivor": 0, "perk": "up-the-ante", "tier": "three" }"#,
+            r#"{ "kind": "offering", "survivor": 1, "offering": "salty-lips" }"#,
+        ] {
+            expected
+                .alter(serde_json::from_str(update).expect("Test updates are valid."))
+                .expect("Test updates are accepted.");
+        }
+
+        assert_eq!(team.0, expected);
+        assert_eq!(
+            team.luck_output(),
+            expected
+                .luck_output(&Ruleset::default())
+                .into_iter()
+                .map(|(single, total)| Odds { single, total })
+                .collect::<Vec<_>>()
+        )
+    }
+
+    #[wasm_bindgen_test]
+    fn json_updates_applied() {
+        let mut team = WasmTeam::default();
+
+        team.apply_update(r#"{ "kind": "alive", "survivor": 3, "alive": false }"#)
+            .expect("Survivor 3 is in a default team.");
+
+        let id = SurvivorId::try_new(3).expect("Test ids are in team capacity.");
+        assert!(!team.0.get_player(id).is_alive())
+    }
+
+    #[wasm_bindgen_test]
+    fn bad_input_refused_without_change() {
+        let mut team = WasmTeam::new(1).expect("One is a team size.");
+
+        assert!(WasmTeam::new(9).is_err());
+        assert!(team.set_perk(0, "slippery-meat", Some(4)).is_err());
+        assert!(team.set_perk(0, "lucky-break", Some(1)).is_err());
+        assert!(team.set_offering(0, Some("chalk".into())).is_err());
+        assert!(team.set_alive(1, false).is_err());
+        assert_eq!(
+            team.0,
+            Team::with_size(TeamSize::try_new(1).expect("One is a team size."))
+        )
+    }
+
+    #[wasm_bindgen_test]
+    fn codes_round_trip() {
+        let mut team = WasmTeam::new(3).expect("Three is a team size.");
+        team.set_lock_condition(2, true)
+            .expect("Lock conditions are never refused.");
+
+        let read = WasmTeam::from_code(&team.to_code()).expect("Written codes are read back.");
+
+        assert_eq!(read.0, team.0);
+        assert_eq!(read.size(), 3)
+    }
+}
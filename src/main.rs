@@ -1,9 +1,20 @@
+mod args;
+mod presets;
+mod session;
 mod ui;
 use ui::App;
 
 fn main() -> iced::Result {
+    let rules = match args::ruleset_from_args() {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2)
+        }
+    };
+
     iced::daemon(App::title, App::update, App::view)
         .subscription(App::subscription)
         .theme(App::theme)
-        .run_with(App::new)
+        .run_with(move || App::new(rules))
 }
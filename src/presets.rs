@@ -0,0 +1,271 @@
+use std::path::{Path, PathBuf};
+
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+use hook_escape_calculator::roster::NamedTeam;
+
+/// The version of the preset file this build writes. Files of a later
+/// version are refused rather than overwritten. Version 2 added survivor
+/// names, which version 1 files simply lack.
+const PRESETS_VERSION: u32 = 2;
+const PRESETS_DIR: &str = "hook-escape-calculator";
+const PRESETS_FILE: &str = "presets.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub team: NamedTeam,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PresetFile {
+    version: u32,
+    presets: Vec<Preset>,
+}
+
+#[derive(Debug, Display)]
+pub enum PresetError {
+    #[display("there is no config directory to keep presets in")]
+    NoConfigDir,
+    #[display("could not access the presets file: {_0}")]
+    Io(std::io::Error),
+    #[display("could not read the presets file: {_0}")]
+    Json(serde_json::Error),
+    #[display("the presets file is version {_0}, which is newer than this calculator")]
+    UnsupportedVersion(u32),
+    #[display("a preset needs a name")]
+    EmptyName,
+    #[display("there is already a preset named \"{_0}\"")]
+    NameTaken(String),
+    #[display("there is no preset named \"{_0}\"")]
+    Unknown(String),
+}
+
+impl std::error::Error for PresetError {}
+
+/// Named teams, kept in a versioned JSON file in the user's config directory
+#[derive(Debug, Clone, Default)]
+pub struct PresetStore {
+    path: Option<PathBuf>,
+    presets: Vec<Preset>,
+}
+
+impl PresetStore {
+    /// Reads the presets file. A store is always returned so that the
+    /// calculator may run without presets; it cannot be saved to if the
+    /// file could not be read, lest it be overwritten.
+    pub fn open() -> (Self, Option<PresetError>) {
+        let Some(path) = dirs::config_dir().map(|x| x.join(PRESETS_DIR).join(PRESETS_FILE)) else {
+            return (Self::default(), Some(PresetError::NoConfigDir));
+        };
+        match Self::open_at(path) {
+            Ok(store) => (store, None),
+            Err(e) => (Self::default(), Some(e)),
+        }
+    }
+
+    /// Reads the presets file at a path, to which every change is written back
+    pub fn open_at(path: PathBuf) -> Result<Self, PresetError> {
+        let presets = read(&path)?;
+        Ok(Self {
+            path: Some(path),
+            presets,
+        })
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.presets.iter().map(|x| x.name.clone()).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NamedTeam> {
+        self.find(name).map(|i| &self.presets[i].team)
+    }
+
+    /// Saves the team under a name, replacing any preset of that name
+    pub fn save(&mut self, name: &str, team: NamedTeam) -> Result<(), PresetError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(PresetError::EmptyName);
+        }
+        let mut presets = self.presets.clone();
+        match self.find(name) {
+            Some(i) => presets[i].team = team,
+            None => presets.push(Preset {
+                name: name.to_owned(),
+                team,
+            }),
+        }
+        self.commit(presets)
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), PresetError> {
+        let to = to.trim();
+        if to.is_empty() {
+            return Err(PresetError::EmptyName);
+        }
+        if from != to && self.find(to).is_some() {
+            return Err(PresetError::NameTaken(to.to_owned()));
+        }
+        let i = self
+            .find(from)
+            .ok_or_else(|| PresetError::Unknown(from.to_owned()))?;
+        let mut presets = self.presets.clone();
+        presets[i].name = to.to_owned();
+        self.commit(presets)
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<(), PresetError> {
+        let i = self
+            .find(name)
+            .ok_or_else(|| PresetError::Unknown(name.to_owned()))?;
+        let mut presets = self.presets.clone();
+        presets.remove(i);
+        self.commit(presets)
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.presets.iter().position(|x| x.name == name)
+    }
+
+    /// Writes the presets to disk, keeping them only if that succeeds
+    fn commit(&mut self, presets: Vec<Preset>) -> Result<(), PresetError> {
+        let path = self.path.as_deref().ok_or(PresetError::NoConfigDir)?;
+        write(path, &presets)?;
+        self.presets = presets;
+        Ok(())
+    }
+}
+
+/// A missing file holds no presets
+fn read(path: &Path) -> Result<Vec<Preset>, PresetError> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(PresetError::Io(e)),
+    };
+    // The version is read first so that a newer file is reported as
+    // such, rather than as whatever it fails to parse as.
+    #[derive(Deserialize)]
+    struct Version {
+        version: u32,
+    }
+    let Version { version } = serde_json::from_str(&text).map_err(PresetError::Json)?;
+    if version > PRESETS_VERSION {
+        return Err(PresetError::UnsupportedVersion(version));
+    }
+    let file: PresetFile = serde_json::from_str(&text).map_err(PresetError::Json)?;
+    Ok(file.presets)
+}
+
+fn write(path: &Path, presets: &[Preset]) -> Result<(), PresetError> {
+    let file = PresetFile {
+        version: PRESETS_VERSION,
+        presets: presets.to_vec(),
+    };
+    let text = serde_json::to_string_pretty(&file).map_err(PresetError::Json)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(PresetError::Io)?;
+    }
+    std::fs::write(path, text).map_err(PresetError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> (tempfile::TempDir, PathBuf, PresetStore) {
+        let dir = tempfile::tempdir().expect("A temporary directory can be made.");
+        let path = dir.path().join(PRESETS_FILE);
+        let store = PresetStore::open_at(path.clone()).expect("A missing file holds no presets.");
+        (dir, path, store)
+    }
+
+    fn reopen(path: &Path) -> Vec<String> {
+        PresetStore::open_at(path.to_owned())
+            .expect("A written file can be read.")
+            .names()
+    }
+
+    #[test]
+    fn every_change_written_back() {
+        let (_dir, path, mut store) = store();
+
+        store
+            .save("duo SM stack", NamedTeam::default())
+            .expect("Saving is allowed.");
+        assert_eq!(reopen(&path), ["duo SM stack"]);
+        store
+            .rename("duo SM stack", "UTA quad")
+            .expect("Renaming is allowed.");
+        assert_eq!(reopen(&path), ["UTA quad"]);
+        store.delete("UTA quad").expect("Deleting is allowed.");
+        assert!(reopen(&path).is_empty())
+    }
+
+    #[test]
+    fn saving_a_name_again_replaces_it() {
+        let (_dir, path, mut store) = store();
+        let mut team = NamedTeam::default();
+
+        store
+            .save("quad", team.clone())
+            .expect("Saving is allowed.");
+        team.roster
+            .get_mut(team.team.ids().next().expect("Teams are not empty."))
+            .set_name("Meg");
+        store
+            .save(" quad ", team.clone())
+            .expect("Saving is allowed.");
+
+        assert_eq!(reopen(&path), ["quad"]);
+        assert_eq!(store.get("quad"), Some(&team))
+    }
+
+    #[test]
+    fn rename_onto_another_preset_refused() {
+        let (_dir, path, mut store) = store();
+        store
+            .save("a", NamedTeam::default())
+            .expect("Saving is allowed.");
+        store
+            .save("b", NamedTeam::default())
+            .expect("Saving is allowed.");
+
+        assert!(matches!(store.rename("a", "b"), Err(PresetError::NameTaken(x)) if x == "b"));
+        assert!(store.rename("a", "a").is_ok());
+        assert!(matches!(
+            store.rename("c", "d"),
+            Err(PresetError::Unknown(_))
+        ));
+        assert_eq!(reopen(&path), ["a", "b"])
+    }
+
+    #[test]
+    fn empty_names_refused() {
+        let (_dir, path, mut store) = store();
+        store
+            .save("a", NamedTeam::default())
+            .expect("Saving is allowed.");
+
+        assert!(matches!(
+            store.save("  ", NamedTeam::default()),
+            Err(PresetError::EmptyName)
+        ));
+        assert!(matches!(store.rename("a", ""), Err(PresetError::EmptyName)));
+        assert!(matches!(store.delete(""), Err(PresetError::Unknown(_))));
+        assert_eq!(reopen(&path), ["a"])
+    }
+
+    #[test]
+    fn newer_file_refused() {
+        let (_dir, path, _) = store();
+        let newer = format!(r#"{{ "version": {}, "presets": [] }}"#, PRESETS_VERSION + 1);
+        std::fs::write(&path, newer).expect("The temporary file can be written.");
+
+        assert!(matches!(
+            PresetStore::open_at(path),
+            Err(PresetError::UnsupportedVersion(x)) if x == PRESETS_VERSION + 1
+        ))
+    }
+}
@@ -0,0 +1,50 @@
+use hook_escape_calculator::{
+    ruleset::{self, Ruleset},
+    server,
+};
+
+const USAGE: &str = "usage: Hook-Escape-Server [--port <port>] [--rules <file.toml|file.json>]";
+const DEFAULT_PORT: u16 = 7474;
+
+struct Args {
+    port: u16,
+    rules: Ruleset,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = match args_from_env() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2)
+        }
+    };
+
+    println!("Serving the calculator on http://127.0.0.1:{}", args.port);
+    if let Err(e) = server::serve(args.port, args.rules).await {
+        eprintln!("{e}");
+        std::process::exit(1)
+    }
+}
+
+fn args_from_env() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1);
+    let mut port = DEFAULT_PORT;
+    let mut rules: Option<Ruleset> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => port = args.next().and_then(|x| x.parse().ok()).ok_or(USAGE)?,
+            "--rules" => {
+                rules = Some(ruleset::from_path_arg(args.next()).map_err(|e| e.to_string())?)
+            }
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    Ok(Args {
+        port,
+        rules: rules.unwrap_or_default(),
+    })
+}
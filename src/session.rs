@@ -0,0 +1,294 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use derive_more::Display;
+
+use hook_escape_calculator::{
+    roster::NamedTeam,
+    session_log::{self, SessionChange, SessionEvent, SessionLogError},
+};
+
+const SESSIONS_DIR: &str = "hook-escape-calculator";
+const SESSIONS_SUBDIR: &str = "sessions";
+const SESSION_EXTENSION: &str = "jsonl";
+
+#[derive(Debug, Display)]
+pub enum SessionError {
+    #[display("there is no data directory to keep sessions in")]
+    NoDataDir,
+    #[display("could not access the session log: {_0}")]
+    Io(std::io::Error),
+    #[display("{_0}")]
+    Log(SessionLogError),
+}
+
+impl std::error::Error for SessionError {}
+
+/// Writes each change of the team to a new session log in the user's
+/// data directory. The log is only created once the team first changes,
+/// so that opening the calculator does not leave an empty session behind.
+#[derive(Debug)]
+pub struct SessionRecorder {
+    /// Where the log is created, if there is anywhere to keep it
+    dir: Option<PathBuf>,
+    file: Option<File>,
+    /// Set once a write fails, after which nothing more is recorded
+    failed: bool,
+}
+
+impl SessionRecorder {
+    /// A recorder which creates its log in the user's data directory
+    pub fn new() -> Self {
+        Self::with_dir(sessions_dir().ok())
+    }
+
+    fn with_dir(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            file: None,
+            failed: false,
+        }
+    }
+
+    /// Records a change to the team, beginning the log with the team as it
+    /// was before the first change. Returns whether the log was created.
+    pub fn record(
+        &mut self,
+        before: &NamedTeam,
+        change: SessionChange,
+    ) -> Result<bool, SessionError> {
+        if self.failed {
+            return Ok(false);
+        }
+        let result = self.write(before, change);
+        self.failed = result.is_err();
+        result
+    }
+
+    fn write(&mut self, before: &NamedTeam, change: SessionChange) -> Result<bool, SessionError> {
+        let created = self.file.is_none();
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => {
+                let dir = self.dir.as_deref().ok_or(SessionError::NoDataDir)?;
+                std::fs::create_dir_all(dir).map_err(SessionError::Io)?;
+                let path = dir.join(format!("{}.{SESSION_EXTENSION}", now_ms()));
+                let mut file = File::create_new(path).map_err(SessionError::Io)?;
+                write_event(&mut file, SessionChange::team(before.clone()))?;
+                self.file.insert(file)
+            }
+        };
+        write_event(file, change)?;
+        Ok(created)
+    }
+}
+
+/// A saved session being stepped through. The team the calculator held
+/// before is kept so that it may be returned to.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub events: Vec<SessionEvent>,
+    /// The team and its names after each event
+    pub teams: Vec<NamedTeam>,
+    pub step: usize,
+    pub before: NamedTeam,
+}
+
+impl Replay {
+    /// Reads a saved session, starting at its first event. A session with
+    /// no events cannot be stepped through and is refused.
+    pub fn open(name: &str, before: NamedTeam) -> Result<Option<Self>, SessionError> {
+        Self::open_in(&sessions_dir()?, name, before)
+    }
+
+    /// Reads a saved session from `dir` rather than the user's data directory
+    pub fn open_in(
+        dir: &Path,
+        name: &str,
+        before: NamedTeam,
+    ) -> Result<Option<Self>, SessionError> {
+        let path = dir.join(format!("{name}.{SESSION_EXTENSION}"));
+        let text = std::fs::read_to_string(path).map_err(SessionError::Io)?;
+        let events = session_log::read_log(&text).map_err(SessionError::Log)?;
+        let teams = session_log::replay_steps(&events).map_err(SessionError::Log)?;
+        Ok((!teams.is_empty()).then_some(Self {
+            events,
+            teams,
+            step: 0,
+            before,
+        }))
+    }
+
+    pub fn team(&self) -> &NamedTeam {
+        &self.teams[self.step]
+    }
+
+    pub fn last_step(&self) -> usize {
+        self.teams.len() - 1
+    }
+
+    /// The time from the start of the session to the current step
+    pub fn elapsed_ms(&self) -> u64 {
+        let start = self.events[0].time_ms();
+        self.events[self.step].time_ms().saturating_sub(start)
+    }
+}
+
+/// The names of the saved sessions, newest first
+pub fn saved_sessions() -> Vec<String> {
+    sessions_dir()
+        .map(|x| saved_sessions_in(&x))
+        .unwrap_or_default()
+}
+
+/// The names of the sessions saved in `dir`, newest first
+pub fn saved_sessions_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|x| x.path())
+        .filter(|x| x.extension().is_some_and(|x| x == SESSION_EXTENSION))
+        .filter_map(|x| Some(x.file_stem()?.to_str()?.to_owned()))
+        .collect();
+    // Names are the time each session began, so longer names are later
+    names.sort_by(|a, b| (b.len(), b).cmp(&(a.len(), a)));
+    names
+}
+
+fn sessions_dir() -> Result<PathBuf, SessionError> {
+    dirs::data_dir()
+        .map(|x| x.join(SESSIONS_DIR).join(SESSIONS_SUBDIR))
+        .ok_or(SessionError::NoDataDir)
+}
+
+fn write_event(file: &mut File, change: SessionChange) -> Result<(), SessionError> {
+    let line = SessionEvent::new(now_ms(), change).to_line() + "\n";
+    file.write_all(line.as_bytes()).map_err(SessionError::Io)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hook_escape_calculator::{
+        roster::SurvivorLabel,
+        update::{SurvivorId, SurvivorUpdate},
+    };
+
+    fn first() -> SurvivorId {
+        SurvivorId::try_new(0).expect("Zero is a survivor.")
+    }
+
+    fn death() -> SessionChange {
+        SessionChange::Update(
+            SurvivorUpdate::living_status()
+                .id(first())
+                .alive(false)
+                .call(),
+        )
+    }
+
+    fn logs(dir: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .map(|x| x.filter_map(Result::ok).map(|x| x.path()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn log_created_on_first_change() {
+        let dir = tempfile::tempdir().expect("A temporary directory can be made.");
+        let sessions = dir.path().join("sessions");
+        let mut recorder = SessionRecorder::with_dir(Some(sessions.clone()));
+        let mut before = NamedTeam::default();
+        before.roster.get_mut(first()).set_name("Ana");
+        assert!(logs(&sessions).is_empty());
+
+        assert!(
+            recorder
+                .record(&before, death())
+                .expect("The log can be written.")
+        );
+        assert!(
+            !recorder
+                .record(&before, death())
+                .expect("The log can be written.")
+        );
+
+        let [log] = logs(&sessions).try_into().expect("One log is created.");
+        let text = std::fs::read_to_string(log).expect("The log can be read.");
+        let changes: Vec<SessionChange> = session_log::read_log(&text)
+            .expect("The log is valid.")
+            .iter()
+            .map(|x| x.change().clone())
+            .collect();
+        assert_eq!(changes, [SessionChange::team(before), death(), death()])
+    }
+
+    #[test]
+    fn recording_stops_after_a_failed_write() {
+        let dir = tempfile::tempdir().expect("A temporary directory can be made.");
+        let file = dir.path().join("not-a-dir");
+        std::fs::write(&file, "").expect("The temporary file can be written.");
+        let mut recorder = SessionRecorder::with_dir(Some(file.join("sessions")));
+
+        assert!(recorder.record(&NamedTeam::default(), death()).is_err());
+        assert!(matches!(
+            recorder.record(&NamedTeam::default(), death()),
+            Ok(false)
+        ));
+    }
+
+    #[test]
+    fn recorded_session_replays() {
+        let dir = tempfile::tempdir().expect("A temporary directory can be made.");
+        let mut recorder = SessionRecorder::with_dir(Some(dir.path().to_owned()));
+        recorder
+            .record(&NamedTeam::default(), death())
+            .expect("The log can be written.");
+        recorder
+            .record(
+                &NamedTeam::default(),
+                SessionChange::label(first(), &SurvivorLabel::new("Ana", Some("Meg"))),
+            )
+            .expect("The log can be written.");
+        let [name] = saved_sessions_in(dir.path())
+            .try_into()
+            .expect("One session is saved.");
+
+        let replay = Replay::open_in(dir.path(), &name, NamedTeam::default())
+            .expect("The session can be read.")
+            .expect("The session has events.");
+        assert_eq!(replay.last_step(), 2);
+        assert_eq!(replay.team(), &NamedTeam::default());
+        assert!(replay.teams[1].team.get_player(first()).is_dead());
+        assert_eq!(replay.teams[2].roster.display_name(first()), "Ana");
+
+        std::fs::write(dir.path().join("1.jsonl"), "").expect("The temporary file can be written.");
+        assert!(matches!(
+            Replay::open_in(dir.path(), "1", NamedTeam::default()),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn sessions_listed_newest_first() {
+        let dir = tempfile::tempdir().expect("A temporary directory can be made.");
+        for name in ["999.jsonl", "1001.jsonl", "1000.jsonl", "notes.txt"] {
+            std::fs::write(dir.path().join(name), "").expect("The temporary file can be written.");
+        }
+
+        assert_eq!(saved_sessions_in(dir.path()), ["1001", "1000", "999"]);
+        assert!(saved_sessions_in(&dir.path().join("missing")).is_empty())
+    }
+}
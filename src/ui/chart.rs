@@ -0,0 +1,134 @@
+use iced::{
+    Color, Point, Rectangle, Renderer, Size, Theme, mouse,
+    widget::canvas::{self, Frame, Geometry, LineDash, Path, Stroke, Text},
+};
+
+use hook_escape_calculator::constants::misc as k;
+
+/// Colours of the survivors' curves, in team order
+pub const SURVIVOR_COLORS: [Color; k::TEAM_MAX_CAPACITY] = [
+    Color::from_rgb(0.55, 0.91, 0.99),
+    Color::from_rgb(0.31, 0.98, 0.48),
+    Color::from_rgb(1.0, 0.72, 0.42),
+    Color::from_rgb(1.0, 0.47, 0.78),
+    Color::from_rgb(0.74, 0.58, 0.98),
+    Color::from_rgb(0.95, 0.98, 0.55),
+    Color::from_rgb(1.0, 0.33, 0.33),
+    Color::from_rgb(0.6, 0.8, 0.6),
+];
+pub const BASELINE_COLOR: Color = Color::from_rgb(0.6, 0.6, 0.6);
+
+const MARGIN_LEFT: f32 = 40.;
+const MARGIN_BOTTOM: f32 = 20.;
+const MARGIN: f32 = 8.;
+const LABEL_SIZE: f32 = 11.;
+
+/// The chance of having escaped by each attempt, for every survivor and
+/// for a survivor with no luck items, drawn as lines from no attempts to
+/// the most any of them has.
+#[derive(Debug)]
+pub struct EscapeChart<'a> {
+    /// Each survivor's chance of having escaped by each attempt
+    pub curves: &'a [Vec<f64>],
+    pub baseline: &'a [f64],
+}
+
+impl<Message> canvas::Program<Message> for EscapeChart<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let text_color = theme.palette().text;
+        let plot = Rectangle::new(
+            Point::new(MARGIN_LEFT, MARGIN),
+            Size::new(
+                bounds.width - MARGIN_LEFT - MARGIN,
+                bounds.height - MARGIN_BOTTOM - MARGIN,
+            ),
+        );
+        let attempts = self
+            .curves
+            .iter()
+            .map(Vec::len)
+            .chain([self.baseline.len()])
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        let point = |attempt: usize, chance: f64| {
+            Point::new(
+                plot.x + plot.width * attempt as f32 / attempts as f32,
+                plot.y + plot.height * (1. - chance as f32),
+            )
+        };
+
+        // Gridlines at every quarter, and a label under every attempt
+        let grid = Stroke::default()
+            .with_color(Color {
+                a: 0.2,
+                ..text_color
+            })
+            .with_width(1.);
+        for quarter in 0..=4 {
+            let chance = f64::from(quarter) / 4.;
+            frame.stroke(&Path::line(point(0, chance), point(attempts, chance)), grid);
+            frame.fill_text(label(
+                format!("{}%", quarter * 25),
+                Point::new(MARGIN, point(0, chance).y - LABEL_SIZE / 2.),
+                text_color,
+            ));
+        }
+        for attempt in 1..=attempts {
+            let at = point(attempt, 0.);
+            frame.fill_text(label(
+                attempt.to_string(),
+                Point::new(at.x - LABEL_SIZE / 4., at.y + 4.),
+                text_color,
+            ));
+        }
+
+        let baseline = Stroke {
+            line_dash: LineDash {
+                segments: &[4., 4.],
+                offset: 0,
+            },
+            ..Stroke::default().with_color(BASELINE_COLOR).with_width(2.)
+        };
+        frame.stroke(&curve_path(self.baseline, attempts, point), baseline);
+        for (curve, color) in self.curves.iter().zip(SURVIVOR_COLORS) {
+            let stroke = Stroke::default().with_color(color).with_width(2.);
+            frame.stroke(&curve_path(curve, attempts, point), stroke);
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// A curve starts at no chance before the first attempt. Once a survivor
+/// is out of attempts their chance stays where it ended.
+fn curve_path(by: &[f64], attempts: usize, point: impl Fn(usize, f64) -> Point) -> Path {
+    Path::new(|builder| {
+        builder.move_to(point(0, 0.));
+        let mut last = 0.;
+        for attempt in 1..=attempts {
+            last = by.get(attempt - 1).copied().unwrap_or(last);
+            builder.line_to(point(attempt, last));
+        }
+    })
+}
+
+fn label(content: String, position: Point, color: Color) -> Text {
+    Text {
+        content,
+        position,
+        color,
+        size: LABEL_SIZE.into(),
+        ..Text::default()
+    }
+}
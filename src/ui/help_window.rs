@@ -46,6 +46,6 @@ const TEXT: [&str; 17] = [
     "/hook_escape_calculator",
 ];
 
-fn about_centered_container(s: &str) -> Container<Message> {
+fn about_centered_container(s: &str) -> Container<'_, Message> {
     container(text(s).center()).center_x(ABOUT_WIDTH)
 }
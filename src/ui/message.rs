@@ -1,12 +1,56 @@
 use iced::window;
 
-use hook_escape_calculator::update::SurvivorUpdate;
+use hook_escape_calculator::{
+    offering::Offering,
+    perk::{PerkName, Tier},
+    team::{Team, TeamSize},
+    update::{SurvivorId, SurvivorUpdate, UpdateError},
+};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+use super::widget_data::{ObjectiveChoice, RulesChoice};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Message {
     UpdateSurvivor(SurvivorUpdate),
+    ToggleBreakdown(SurvivorId),
+    RenameSurvivor(SurvivorId, String),
+    SetCharacter(SurvivorId, String),
+    QuickSelect(SurvivorId),
+    QuickDeselect,
+    QuickToggleDead,
+    QuickCyclePerk(PerkName),
+    QuickCycleOffering,
+    Undo,
+    Redo,
+    SelectRules(RulesChoice),
+    ResizeTeam(TeamSize),
+    SelectObjective(ObjectiveChoice),
+    Suggest,
+    /// The suggestion for the team it was searched from
+    Suggested(Team, Result<Team, UpdateError>),
+    ToggleOwnership,
+    SetOwnedTier(SurvivorId, PerkName, Option<Tier>),
+    SetOwnsOffering(SurvivorId, Offering, bool),
+    SetKeptFreeSlots(SurvivorId, usize),
+    PresetNameChanged(String),
+    SelectPreset(String),
+    SavePreset,
+    LoadPreset,
+    RenamePreset,
+    DeletePreset,
+    CopyCode,
+    PasteCode,
+    PastedCode(Option<String>),
+    SelectSession(String),
+    OpenSession,
+    ScrubTo(usize),
+    KeepReplay,
+    CloseReplay,
     OpenHelp,
     CloseHelp,
+    ToggleOverlay,
+    CloseOverlay,
+    DragOverlay,
     ExitApp,
     StartApp,
     CloseWindow(window::Id),
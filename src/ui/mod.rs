@@ -1,5 +1,7 @@
+mod chart;
 mod help_window;
 mod message;
+mod overlay_window;
 mod state;
 mod subscription;
 mod update;
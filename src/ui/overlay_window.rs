@@ -0,0 +1,73 @@
+use iced::{
+    Color, Element, Length, Padding, Theme,
+    widget::{Column, mouse_area, row, text},
+    window,
+};
+
+use hook_escape_calculator::constants::misc as k;
+
+use super::{Calculator, Message};
+
+const OVERLAY_WIDTH: f32 = 280.;
+const ROW_HEIGHT: f32 = 24.;
+const PADDING: f32 = 8.;
+/// How much of the game shows through the overlay, from 0 to 1
+const OVERLAY_OPACITY: f32 = 0.75;
+
+/// A small borderless window which floats above the game. It is tall
+/// enough for the largest team, so it need not resize with the team.
+pub fn window_settings() -> window::Settings {
+    let height = ROW_HEIGHT * k::TEAM_MAX_CAPACITY as f32 + 2. * PADDING;
+
+    window::Settings {
+        size: iced::Size::new(OVERLAY_WIDTH, height),
+        resizable: false,
+        decorations: false,
+        transparent: true,
+        level: window::Level::AlwaysOnTop,
+        ..window::Settings::default()
+    }
+}
+
+/// The main theme with a see-through background. Daemon windows take
+/// their background from their theme, so this is what makes the overlay
+/// semi-transparent.
+pub fn theme() -> Theme {
+    let mut palette = Theme::Dracula.palette();
+    palette.background = Color {
+        a: OVERLAY_OPACITY,
+        ..palette.background
+    };
+    Theme::custom(String::from("Overlay"), palette)
+}
+
+/// Each survivor's name, whether they are alive and their total chance.
+/// Without a title bar, the overlay is moved by dragging anywhere on it.
+pub fn view(calculator: &Calculator) -> Element<'_, Message> {
+    let make_row = |id| {
+        let player = calculator.team.get_player(id);
+        let (_, total_chance) = calculator
+            .widgets
+            .odds
+            .get(*id)
+            .expect("Team ids always have odds.");
+        let status = if player.is_alive() { "" } else { "dead" };
+        row![
+            text(calculator.roster.display_name(id)).width(Length::Fill),
+            text(status).width(50),
+            text(total_chance.to_owned()).width(70)
+        ]
+        .height(ROW_HEIGHT)
+    };
+
+    let rows = calculator
+        .team
+        .ids()
+        .map(make_row)
+        .fold(Column::new(), Column::push)
+        .padding(Padding::new(PADDING))
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    mouse_area(rows).on_press(Message::DragOverlay).into()
+}
@@ -1,19 +1,41 @@
+use std::collections::HashSet;
+
 use iced::{Size, Task, window};
 
-use hook_escape_calculator::team;
+use hook_escape_calculator::{
+    history::TeamHistory,
+    optimizer::Constraints,
+    roster::{NamedTeam, Roster},
+    ruleset::Ruleset,
+    team,
+    update::SurvivorId,
+};
+
+use crate::{
+    presets::PresetStore,
+    session::{self, Replay, SessionRecorder},
+};
 
-use super::{message::Message, widget_data::WidgetData};
+use super::{
+    message::Message,
+    widget_data::{ObjectiveChoice, RulesChoice, WidgetData},
+};
 
-#[derive(Debug, Clone)]
+pub const MAIN_WINDOW_WIDTH: f32 = 1390.;
+const MAIN_WINDOW_HEIGHT: f32 = 820.;
+
+#[derive(Debug)]
 pub struct App {
     pub calculator: Calculator,
     pub main_window: window::Id,
     pub help_window: Option<iced::window::Id>,
+    /// The always-on-top overlay, if open
+    pub overlay_window: Option<iced::window::Id>,
 }
 
 impl App {
-    pub fn new() -> (Self, Task<Message>) {
-        let main_window_size = Size::new(1054., 384.);
+    pub fn new(file_rules: Option<Ruleset>) -> (Self, Task<Message>) {
+        let main_window_size = Size::new(MAIN_WINDOW_WIDTH, MAIN_WINDOW_HEIGHT);
 
         let main_window_settings = window::Settings {
             size: main_window_size,
@@ -25,9 +47,10 @@ impl App {
 
         (
             App {
-                calculator: Calculator::default(),
+                calculator: Calculator::new(file_rules),
                 main_window: id,
                 help_window: None,
+                overlay_window: None,
             },
             open.map(|_| Message::StartApp),
         )
@@ -37,6 +60,8 @@ impl App {
             "Hook Calculator"
         } else if Some(id) == self.help_window {
             "Hook Calculator \u{2012} Help"
+        } else if Some(id) == self.overlay_window {
+            "Hook Calculator \u{2012} Overlay"
         } else {
             "Hook Calculator \u{2012} Other"
         };
@@ -44,16 +69,83 @@ impl App {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Calculator {
     pub team: team::Team,
+    /// What each survivor is called, kept through resizing
+    pub roster: Roster,
+    /// The survivor the quick entry keys act on
+    pub quick_survivor: Option<SurvivorId>,
+    /// The survivors whose luck breakdown is open
+    pub expanded_breakdowns: HashSet<SurvivorId>,
+    /// Earlier and undone versions of the team, for undo and redo
+    pub history: TeamHistory,
+    /// The survivor whose name or character is being typed, with the team
+    /// from before the typing began, until it is written to the session log
+    pub labelling: Option<(SurvivorId, NamedTeam)>,
+    pub rules: Ruleset,
+    pub rules_choice: RulesChoice,
+    /// Rules read from the file given on the command line, if any
+    pub file_rules: Option<Ruleset>,
+    /// What the Suggest button optimizes for
+    pub objective: ObjectiveChoice,
+    /// The luck items each survivor owns, which the Suggest button may give them
+    pub constraints: Constraints,
+    /// Whether the table of owned items is shown in place of the team
+    pub show_ownership: bool,
+    /// Whether a suggestion is being searched for
+    pub suggesting: bool,
+    pub presets: PresetStore,
+    /// The name typed into the preset name box
+    pub preset_name: String,
+    pub selected_preset: Option<String>,
+    /// The outcome of the last preset or team code action, shown beside the presets
+    pub status: String,
+    /// Writes every change of the team to this session's log
+    pub recorder: SessionRecorder,
+    pub selected_session: Option<String>,
+    /// The saved session being stepped through, if any
+    pub replay: Option<Replay>,
     pub widgets: WidgetData,
 }
 
-impl std::default::Default for Calculator {
-    fn default() -> Self {
+impl Calculator {
+    pub fn new(file_rules: Option<Ruleset>) -> Self {
         let team = team::Team::default();
-        let widgets = WidgetData::from_team(&team);
-        Calculator { team, widgets }
+        let (rules_choice, rules) = match file_rules {
+            Some(rules) => (RulesChoice::File, rules),
+            None => (RulesChoice::Live, Ruleset::default()),
+        };
+        let (presets, preset_error) = PresetStore::open();
+        let widgets = WidgetData::from_team(
+            &team,
+            &rules,
+            file_rules.is_some(),
+            &presets,
+            session::saved_sessions(),
+        );
+        Calculator {
+            team,
+            roster: Roster::default(),
+            quick_survivor: None,
+            expanded_breakdowns: HashSet::new(),
+            history: TeamHistory::default(),
+            labelling: None,
+            rules,
+            rules_choice,
+            file_rules,
+            objective: ObjectiveChoice::default(),
+            constraints: Constraints::owns_everything(),
+            show_ownership: false,
+            suggesting: false,
+            presets,
+            preset_name: String::new(),
+            selected_preset: None,
+            status: preset_error.map(|e| e.to_string()).unwrap_or_default(),
+            recorder: SessionRecorder::new(),
+            selected_session: None,
+            replay: None,
+            widgets,
+        }
     }
 }
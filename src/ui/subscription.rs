@@ -1,9 +1,64 @@
-use iced::{Subscription, window};
+use iced::{
+    Subscription,
+    keyboard::{self, Key, Modifiers},
+    window,
+};
+
+use hook_escape_calculator::{perk::PerkName, update::SurvivorId};
 
 use super::{App, Message};
 
 impl App {
     pub fn subscription(&self) -> Subscription<Message> {
-        window::close_events().map(Message::CloseWindow)
+        Subscription::batch([
+            window::close_events().map(Message::CloseWindow),
+            keyboard::on_key_press(shortcut),
+        ])
+    }
+}
+
+/// Ctrl+Z undoes and Ctrl+Shift+Z redoes, with Cmd in place of Ctrl on
+/// macOS. Without modifiers, the quick entry keys of `QUICK_KEYS` act on
+/// the selected survivor. Keys typed into a text box never get here.
+fn shortcut(key: Key, modifiers: Modifiers) -> Option<Message> {
+    match key.as_ref() {
+        Key::Character(c) if modifiers.command() && c.eq_ignore_ascii_case("z") => {
+            Some(if modifiers.shift() {
+                Message::Redo
+            } else {
+                Message::Undo
+            })
+        }
+        _ if modifiers.command() || modifiers.alt() => None,
+        Key::Named(keyboard::key::Named::Escape) => Some(Message::QuickDeselect),
+        Key::Character(c) => quick_key(&c.to_ascii_lowercase()),
+        _ => None,
     }
 }
+
+fn quick_key(c: &str) -> Option<Message> {
+    match c {
+        "d" => Some(Message::QuickToggleDead),
+        "s" => Some(Message::QuickCyclePerk(PerkName::SlipperyMeat)),
+        "u" => Some(Message::QuickCyclePerk(PerkName::UpTheAnte)),
+        "o" => Some(Message::QuickCycleOffering),
+        // Survivors are numbered from one, as in the grid
+        _ => {
+            let n: usize = c.parse().ok()?;
+            SurvivorId::try_new(n.checked_sub(1)?)
+                .ok()
+                .map(Message::QuickSelect)
+        }
+    }
+}
+
+/// The quick entry keys and what they do, for the legend under the grid
+pub const QUICK_KEYS: [(&str, &str); 7] = [
+    ("1\u{2013}8", "select survivor"),
+    ("D", "dead / alive"),
+    ("S", "cycle Slippery Meat"),
+    ("U", "cycle Up the Ante"),
+    ("O", "cycle offering"),
+    ("Esc", "deselect"),
+    ("Ctrl+Z / Ctrl+Shift+Z", "undo / redo"),
+];
@@ -1,8 +1,25 @@
 use iced::{Task, window};
 
-use hook_escape_calculator::update::SurvivorUpdate;
+use hook_escape_calculator::{
+    offering::{Offering, OfferingSlot},
+    optimizer::Objective,
+    patch,
+    perk::{PerkName, Tier, TierSlot},
+    roster::{NamedTeam, SurvivorLabel},
+    session_log::SessionChange,
+    team::{Team, TeamSize},
+    update::{SurvivorId, SurvivorUpdate, UpdateError},
+};
 
-use super::{App, Calculator, Message, help_window};
+use crate::{
+    presets::PresetError,
+    session::{self, Replay},
+};
+
+use super::{
+    App, Calculator, Message, help_window, overlay_window,
+    widget_data::{ObjectiveChoice, RulesChoice},
+};
 
 impl App {
     pub fn update(&mut self, message: Message) -> Task<Message> {
@@ -12,7 +29,150 @@ impl App {
                 self.calculator.update_survivor(x);
                 Task::none()
             }
-            Message::ExitApp => iced::exit(),
+            Message::ToggleBreakdown(id) => {
+                let expanded = &mut self.calculator.expanded_breakdowns;
+                if !expanded.remove(&id) {
+                    expanded.insert(id);
+                }
+                Task::none()
+            }
+            Message::RenameSurvivor(id, x) => {
+                self.calculator.relabel(id, |label| label.set_name(&x));
+                Task::none()
+            }
+            Message::SetCharacter(id, x) => {
+                self.calculator
+                    .relabel(id, |label| label.set_character(Some(&x)));
+                Task::none()
+            }
+            Message::QuickSelect(id) => {
+                self.calculator.quick_select(id);
+                Task::none()
+            }
+            Message::QuickDeselect => {
+                self.calculator.quick_survivor = None;
+                Task::none()
+            }
+            Message::QuickToggleDead => {
+                self.calculator.quick_toggle_dead();
+                Task::none()
+            }
+            Message::QuickCyclePerk(x) => {
+                self.calculator.quick_cycle_perk(x);
+                Task::none()
+            }
+            Message::QuickCycleOffering => {
+                self.calculator.quick_cycle_offering();
+                Task::none()
+            }
+            Message::Undo => {
+                self.calculator.undo();
+                Task::none()
+            }
+            Message::Redo => {
+                self.calculator.redo();
+                Task::none()
+            }
+            Message::SelectRules(x) => {
+                self.calculator.select_rules(x);
+                Task::none()
+            }
+            Message::ResizeTeam(x) => {
+                self.calculator.resize_team(x);
+                Task::none()
+            }
+            Message::SelectObjective(x) => {
+                self.calculator.objective = x;
+                Task::none()
+            }
+            Message::Suggest => self.calculator.suggest(),
+            Message::Suggested(from, x) => {
+                self.calculator.suggested(from, x);
+                Task::none()
+            }
+            Message::ToggleOwnership => {
+                self.calculator.show_ownership = !self.calculator.show_ownership;
+                Task::none()
+            }
+            Message::SetOwnedTier(id, name, tier) => {
+                self.calculator
+                    .constraints
+                    .get_mut(id)
+                    .set_owned_tier(name, tier);
+                Task::none()
+            }
+            Message::SetOwnsOffering(id, offering, owned) => {
+                self.calculator
+                    .constraints
+                    .get_mut(id)
+                    .set_owns_offering(offering, owned);
+                Task::none()
+            }
+            Message::SetKeptFreeSlots(id, slots) => {
+                self.calculator
+                    .constraints
+                    .get_mut(id)
+                    .set_kept_free_slots(slots);
+                Task::none()
+            }
+            Message::PresetNameChanged(x) => {
+                self.calculator.preset_name = x;
+                Task::none()
+            }
+            Message::SelectPreset(x) => {
+                self.calculator.preset_name.clone_from(&x);
+                self.calculator.selected_preset = Some(x);
+                Task::none()
+            }
+            Message::SavePreset => {
+                self.calculator.save_preset();
+                Task::none()
+            }
+            Message::LoadPreset => {
+                self.calculator.load_preset();
+                Task::none()
+            }
+            Message::RenamePreset => {
+                self.calculator.rename_preset();
+                Task::none()
+            }
+            Message::DeletePreset => {
+                self.calculator.delete_preset();
+                Task::none()
+            }
+            Message::CopyCode => {
+                self.calculator.status = String::from("Copied team code");
+                iced::clipboard::write(self.calculator.named_team().to_code())
+            }
+            Message::PasteCode => iced::clipboard::read().map(Message::PastedCode),
+            Message::PastedCode(x) => {
+                self.calculator.paste_code(x);
+                Task::none()
+            }
+            Message::SelectSession(x) => {
+                self.calculator.selected_session = Some(x);
+                Task::none()
+            }
+            Message::OpenSession => {
+                self.calculator.open_session();
+                Task::none()
+            }
+            Message::ScrubTo(x) => {
+                self.calculator.scrub_to(x);
+                Task::none()
+            }
+            Message::KeepReplay => {
+                self.calculator.keep_replay();
+                Task::none()
+            }
+            Message::CloseReplay => {
+                self.calculator.close_replay();
+                Task::none()
+            }
+            Message::ExitApp => {
+                self.calculator.finish_labelling();
+                iced::exit()
+            }
             Message::StartApp => Task::none(),
             Message::CloseHelp => {
                 self.help_window = None;
@@ -28,6 +188,20 @@ impl App {
                 self.help_window = Some(id);
                 open.map(|_| Message::Noop)
             }
+            // The overlay is forgotten once it reports being closed
+            Message::ToggleOverlay => match self.overlay_window {
+                Some(id) => window::close(id),
+                None => {
+                    let (id, open) = window::open(overlay_window::window_settings());
+                    self.overlay_window = Some(id);
+                    open.map(|_| Message::Noop)
+                }
+            },
+            Message::CloseOverlay => {
+                self.overlay_window = None;
+                Task::none()
+            }
+            Message::DragOverlay => self.overlay_window.map_or_else(Task::none, window::drag),
             Message::CloseWindow(id) => self.update(self.specify_close(id)),
         }
     }
@@ -35,16 +209,339 @@ impl App {
     fn specify_close(&self, id: window::Id) -> Message {
         if id == self.main_window {
             Message::ExitApp
-        } else {
+        } else if Some(id) == self.overlay_window {
+            Message::CloseOverlay
+        } else if Some(id) == self.help_window {
             Message::CloseHelp
+        } else {
+            Message::Noop
         }
     }
 }
 
 impl Calculator {
     fn update_survivor(&mut self, survivor_update: SurvivorUpdate) {
-        self.team.alter(survivor_update);
+        self.edit(SessionChange::Update(survivor_update));
+    }
+    /// Searches for the best luck items among those each survivor owns.
+    /// A large team may take a moment, so the search is run away from the
+    /// window, which stays responsive meanwhile.
+    fn suggest(&mut self) -> Task<Message> {
+        let team = self.team;
+        let rules = self.rules;
+        let constraints = self.constraints.clone();
+        let objective = self.objective.0;
+        self.suggesting = true;
+        Task::perform(
+            async move { team.optimize(&rules, &constraints, objective) },
+            move |x| Message::Suggested(team, x),
+        )
+    }
+    /// Replaces the team's luck items with the suggestion, unless the team
+    /// was edited while it was searched for
+    fn suggested(&mut self, from: Team, result: Result<Team, UpdateError>) {
+        self.suggesting = false;
+        match result {
+            Ok(_) if from != self.team => {
+                self.status = String::from("The team changed before the suggestion was ready")
+            }
+            Ok(team) => self.edit_team(team),
+            Err(e) => self.status = e.to_string(),
+        }
+    }
+    fn resize_team(&mut self, size: TeamSize) {
+        let mut team = self.team;
+        team.resize(size);
+        self.edit_team(team);
+    }
+    fn edit_team(&mut self, team: Team) {
+        self.edit(SessionChange::team(NamedTeam::new(
+            team,
+            self.roster.clone(),
+        )));
+    }
+    /// Renames a survivor as an edit, so that names are undone and
+    /// recorded along with the team. Each key typed into one survivor's
+    /// name or character box joins the edit begun by the first, so that
+    /// the typing is undone at once and recorded as one change.
+    fn relabel(&mut self, id: SurvivorId, change: impl FnOnce(&mut SurvivorLabel)) {
+        let mut label = self.roster.get(id).clone();
+        change(&mut label);
+        match &self.labelling {
+            Some((survivor, _)) if *survivor == id => *self.roster.get_mut(id) = label,
+            _ => self.edit(SessionChange::label(id, &label)),
+        }
+    }
+    /// Writes the survivor's label being typed, if any, to the session log
+    fn finish_labelling(&mut self) {
+        if let Some((id, before)) = self.labelling.take() {
+            let change = SessionChange::label(id, self.roster.get(id));
+            self.record(&before, change);
+        }
+    }
+    /// Applies a change to the team or its names, remembering them as
+    /// they were before so that the edit may be undone, and recording it
+    /// in the session log. An edit which changes nothing is neither
+    /// remembered nor recorded.
+    ///
+    /// Editing a replayed team carries on from it, so the edit is taken
+    /// from the team held before the replay was opened. A survivor's label
+    /// is recorded once the typing of it ends.
+    fn edit(&mut self, change: SessionChange) {
+        self.finish_labelling();
+        let shown = self.named_team();
+        let mut named = shown.clone();
+        // A refused update leaves the team as it was, which the
+        // widgets already display.
+        if change.apply(&mut named).is_err() {
+            return;
+        }
+        let (before, change) = match self.replay.take() {
+            Some(replay) => (replay.before, SessionChange::team(named.clone())),
+            None => (shown.clone(), change),
+        };
+        if named != before {
+            self.history.record(before.clone());
+            match change {
+                SessionChange::Label { survivor, .. } => self.labelling = Some((survivor, before)),
+                change => self.record(&before, change),
+            }
+        }
+        if named != shown {
+            self.replace_team(named);
+        }
+    }
+    fn undo(&mut self) {
+        self.finish_labelling();
+        self.close_replay();
+        let current = self.named_team();
+        if let Some(named) = self.history.undo(current.clone()) {
+            self.record(&current, SessionChange::team(named.clone()));
+            self.replace_team(named);
+        }
+    }
+    fn redo(&mut self) {
+        self.finish_labelling();
+        self.close_replay();
+        let current = self.named_team();
+        if let Some(named) = self.history.redo(current.clone()) {
+            self.record(&current, SessionChange::team(named.clone()));
+            self.replace_team(named);
+        }
+    }
+    /// Writes a change to the session log. A failure is shown once, after
+    /// which the session goes unrecorded.
+    fn record(&mut self, before: &NamedTeam, change: SessionChange) {
+        match self.recorder.record(before, change) {
+            Ok(true) => self.widgets.session_names = session::saved_sessions(),
+            Ok(false) => {}
+            Err(e) => self.status = e.to_string(),
+        }
+    }
+    /// Swaps in a team, which may be of a different size, along with its
+    /// survivors' names
+    fn replace_team(&mut self, named: NamedTeam) {
+        self.team = named.team;
+        self.roster = named.roster;
+        if let Objective::Survivor(id) = self.objective.0
+            && !self.team.contains(id)
+        {
+            self.objective = ObjectiveChoice::default();
+        }
+        if let Some(id) = self.quick_survivor
+            && !self.team.contains(id)
+        {
+            self.quick_survivor = None;
+        }
+        self.widgets.renew_team_size(&self.team);
+        self.widgets.renew_odds(&self.team, &self.rules);
+    }
+}
 
-        self.widgets.renew_odds(&self.team);
+// Quick entry
+impl Calculator {
+    fn quick_select(&mut self, id: SurvivorId) {
+        if self.team.contains(id) {
+            self.quick_survivor = Some(id);
+        }
+    }
+    fn quick_toggle_dead(&mut self) {
+        let Some(id) = self.quick_survivor else {
+            return;
+        };
+        let alive = self.team.get_player(id).is_dead();
+        self.update_survivor(SurvivorUpdate::living_status().id(id).alive(alive).call());
+    }
+    /// Steps the perk to its next tier, then off, then to tier one again
+    fn quick_cycle_perk(&mut self, perk: PerkName) {
+        let Some(id) = self.quick_survivor else {
+            return;
+        };
+        let tier = self.team.get_player(id).get_perk_tier(perk).copied();
+        let next = cycle(tier, Tier::iterator());
+        self.update_survivor(
+            SurvivorUpdate::perk()
+                .id(id)
+                .perk(perk)
+                .tier(TierSlot::new(next))
+                .call(),
+        );
+    }
+    /// Steps through every offering, then none
+    fn quick_cycle_offering(&mut self) {
+        let Some(id) = self.quick_survivor else {
+            return;
+        };
+        let offering = self.team.get_player(id).offering().into_inner();
+        let next = cycle(offering, Offering::iterator());
+        self.update_survivor(
+            SurvivorUpdate::offering()
+                .id(id)
+                .offering(OfferingSlot::new(next))
+                .call(),
+        );
+    }
+}
+
+/// The choice after `current` among none and then each of `choices`,
+/// going back to none after the last
+fn cycle<T: PartialEq>(current: Option<T>, choices: impl Iterator<Item = T>) -> Option<T> {
+    let mut all: Vec<Option<T>> = std::iter::once(None).chain(choices.map(Some)).collect();
+    let next = all
+        .iter()
+        .position(|x| *x == current)
+        .map_or(0, |i| (i + 1) % all.len());
+    all.swap_remove(next)
+}
+
+// Presets
+impl Calculator {
+    fn save_preset(&mut self) {
+        let name = self.preset_name.trim().to_owned();
+        let result = self.presets.save(&name, self.named_team());
+        self.finish_preset_action(result, Some(name), "Saved");
+    }
+    fn load_preset(&mut self) {
+        let Some(name) = self.selected_preset.clone() else {
+            return;
+        };
+        match self.presets.get(&name).cloned() {
+            Some(named) => {
+                self.load_named_team(named);
+                self.status = format!("Loaded \"{name}\"");
+            }
+            None => self.status = PresetError::Unknown(name).to_string(),
+        }
+    }
+    fn rename_preset(&mut self) {
+        let Some(from) = self.selected_preset.clone() else {
+            return;
+        };
+        let to = self.preset_name.trim().to_owned();
+        let result = self.presets.rename(&from, &to);
+        self.finish_preset_action(result, Some(to), "Renamed");
+    }
+    fn delete_preset(&mut self) {
+        let Some(name) = self.selected_preset.clone() else {
+            return;
+        };
+        let result = self.presets.delete(&name);
+        self.finish_preset_action(result, None, "Deleted");
+    }
+    /// Reports the outcome of a change to the store and selects the
+    /// preset it leaves behind, if any.
+    fn finish_preset_action(
+        &mut self,
+        result: Result<(), PresetError>,
+        selected: Option<String>,
+        verb: &str,
+    ) {
+        self.status = match result {
+            Ok(()) => {
+                self.selected_preset = selected;
+                self.widgets.renew_presets(&self.presets);
+                verb.to_owned()
+            }
+            Err(e) => e.to_string(),
+        };
+    }
+    fn paste_code(&mut self, code: Option<String>) {
+        let Some(code) = code else {
+            self.status = String::from("The clipboard holds no text");
+            return;
+        };
+        // A bad code leaves the team as it was
+        match NamedTeam::from_code(&code) {
+            Ok(named) => {
+                self.load_named_team(named);
+                self.status = String::from("Pasted team code");
+            }
+            Err(e) => self.status = e.to_string(),
+        }
+    }
+    fn named_team(&self) -> NamedTeam {
+        NamedTeam::new(self.team, self.roster.clone())
+    }
+    /// Swaps in a saved team as an edit, along with its survivors' names
+    fn load_named_team(&mut self, named: NamedTeam) {
+        self.edit(SessionChange::team(named));
+    }
+    fn select_rules(&mut self, choice: RulesChoice) {
+        let rules = match choice {
+            RulesChoice::Live => Ok(Default::default()),
+            RulesChoice::Patch(version) => patch::ruleset_for(version).map_err(|_| ()),
+            RulesChoice::File => self.file_rules.ok_or(()),
+        };
+        // Every choice offered by the picker has rules, so nothing is refused in practice
+        if let Ok(rules) = rules {
+            self.rules = rules;
+            self.rules_choice = choice;
+            self.widgets.renew_odds(&self.team, &self.rules);
+        }
+    }
+}
+
+// Session replay
+impl Calculator {
+    fn open_session(&mut self) {
+        let Some(name) = self.selected_session.clone() else {
+            return;
+        };
+        self.finish_labelling();
+        let before = match &self.replay {
+            Some(replay) => replay.before.clone(),
+            None => self.named_team(),
+        };
+        match Replay::open(&name, before) {
+            Ok(Some(replay)) => {
+                self.status = format!("Replaying session {name}");
+                let team = replay.team().clone();
+                self.replay = Some(replay);
+                self.replace_team(team);
+            }
+            Ok(None) => self.status = format!("Session {name} is empty"),
+            Err(e) => self.status = e.to_string(),
+        }
+    }
+    fn scrub_to(&mut self, step: usize) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+        replay.step = step.min(replay.last_step());
+        let team = replay.team().clone();
+        self.replace_team(team);
+    }
+    /// Ends the replay, keeping the replayed team as an edit of the team
+    /// held before
+    fn keep_replay(&mut self) {
+        if self.replay.is_some() {
+            self.edit_team(self.team);
+        }
+    }
+    /// Ends the replay, returning to the team held before
+    fn close_replay(&mut self) {
+        if let Some(replay) = self.replay.take() {
+            self.replace_team(replay.before);
+        }
     }
 }
@@ -1,58 +1,209 @@
 use iced::{
-    Element, Padding,
-    widget::{Column, Row, button, checkbox, column, combo_box, container, row, text},
+    Element, Length, Padding,
+    widget::{
+        Column, Container, Row, button, canvas, checkbox, column, combo_box, container,
+        horizontal_space, pick_list, row, scrollable, slider, text, text_input,
+    },
     window,
 };
 
 use hook_escape_calculator::{
     constants::misc as k,
-    offering::OfferingSlot,
-    perk::{PerkName, TierSlot},
-    update::{SurvivorId, SurvivorIdError, SurvivorUpdate},
+    offering::{Offering, OfferingSlot},
+    perk::{PerkName, Tier, TierSlot},
+    update::{SurvivorId, SurvivorUpdate},
 };
 
 use super::{
-    App, Calculator, Message, help_window,
-    widget_data::{OfferingSlotDisplay, TierSlotDisplay},
+    App, Calculator, Message,
+    chart::{BASELINE_COLOR, EscapeChart, SURVIVOR_COLORS},
+    help_window, overlay_window,
+    state::MAIN_WINDOW_WIDTH,
+    subscription::QUICK_KEYS,
+    widget_data::{OfferingSlotDisplay, TierSlotDisplay, describe_source, format_signed_percent},
 };
 
+const CHART_WIDTH: f32 = 480.;
+const CHART_HEIGHT: f32 = 180.;
+
 impl App {
-    pub fn view(&self, window_id: window::Id) -> Element<Message> {
+    pub fn view(&self, window_id: window::Id) -> Element<'_, Message> {
         if window_id == self.main_window {
             self.calculator.view()
+        } else if Some(window_id) == self.overlay_window {
+            overlay_window::view(&self.calculator)
         } else {
             help_window::view()
         }
     }
 
-    pub fn theme(&self, _: window::Id) -> iced::Theme {
-        iced::Theme::Dracula
+    pub fn theme(&self, window_id: window::Id) -> iced::Theme {
+        if Some(window_id) == self.overlay_window {
+            overlay_window::theme()
+        } else {
+            iced::Theme::Dracula
+        }
     }
 }
 
 impl Calculator {
-    pub fn view(&self) -> Element<Message> {
+    pub fn view(&self) -> Element<'_, Message> {
         column![
-            container(button("About").on_press(Message::OpenHelp))
-                .align_right(1054)
-                .align_top(40),
-            self.view_team()
+            self.view_header(),
+            self.view_presets(),
+            self.view_session(),
+            container(match self.show_ownership {
+                true => self.view_ownership(),
+                false => self.view_team(),
+            })
+            .height(Length::Fill),
+            self.view_bottom(),
+            view_legend()
+        ]
+        .into()
+    }
+
+    fn view_header(&self) -> Element<'_, Message> {
+        row![
+            text("Game Rules"),
+            pick_list(
+                self.widgets.rules_choices.as_slice(),
+                Some(self.rules_choice),
+                Message::SelectRules
+            ),
+            text("Survivors"),
+            pick_list(
+                self.widgets.size_choices.as_slice(),
+                Some(self.team.size()),
+                Message::ResizeTeam
+            ),
+            button("Undo").on_press_maybe(self.history.can_undo().then_some(Message::Undo)),
+            button("Redo").on_press_maybe(self.history.can_redo().then_some(Message::Redo)),
+            horizontal_space(),
+            text("Optimize For"),
+            pick_list(
+                self.widgets.objective_choices.as_slice(),
+                Some(self.objective),
+                Message::SelectObjective
+            ),
+            button("Suggest").on_press_maybe((!self.suggesting).then_some(Message::Suggest)),
+            button(if self.show_ownership { "Team" } else { "Owned" })
+                .on_press(Message::ToggleOwnership),
+            button("Overlay").on_press(Message::ToggleOverlay),
+            button("About").on_press(Message::OpenHelp)
         ]
+        .spacing(10)
+        .padding(Padding::ZERO.left(10))
+        .align_y(iced::alignment::Vertical::Center)
+        .width(MAIN_WINDOW_WIDTH)
+        .height(40)
         .into()
     }
 
-    fn view_team(&self) -> Element<Message> {
-        let mut rows = Column::new();
+    fn view_presets(&self) -> Element<'_, Message> {
+        let with_selection = |message: Message| self.selected_preset.as_ref().map(|_| message);
+
+        row![
+            text("Preset"),
+            text_input("Preset name", &self.preset_name)
+                .on_input(Message::PresetNameChanged)
+                .on_submit(Message::SavePreset)
+                .width(200),
+            button("Save").on_press(Message::SavePreset),
+            pick_list(
+                self.widgets.preset_names.as_slice(),
+                self.selected_preset.clone(),
+                Message::SelectPreset
+            )
+            .placeholder("Saved presets")
+            .width(200),
+            button("Load").on_press_maybe(with_selection(Message::LoadPreset)),
+            button("Rename").on_press_maybe(with_selection(Message::RenamePreset)),
+            button("Delete").on_press_maybe(with_selection(Message::DeletePreset)),
+            button("Copy code").on_press(Message::CopyCode),
+            button("Paste code").on_press(Message::PasteCode),
+            text(&self.status)
+        ]
+        .spacing(10)
+        .padding(Padding::ZERO.left(10))
+        .align_y(iced::alignment::Vertical::Center)
+        .height(40)
+        .into()
+    }
+
+    /// Picks a saved session and steps through it, showing the team as it
+    /// was after each change
+    fn view_session(&self) -> Element<'_, Message> {
+        let picker = row![
+            text("Session"),
+            pick_list(
+                self.widgets.session_names.as_slice(),
+                self.selected_session.clone(),
+                Message::SelectSession
+            )
+            .placeholder("Saved sessions")
+            .width(200),
+            button("Replay")
+                .on_press_maybe(self.selected_session.as_ref().map(|_| Message::OpenSession))
+        ]
+        .spacing(10)
+        .align_y(iced::alignment::Vertical::Center);
+
+        let scrubber: Element<'_, Message> = match &self.replay {
+            None => horizontal_space().into(),
+            Some(replay) => {
+                let step = replay.step;
+                let last = replay.last_step();
+                let elapsed = replay.elapsed_ms() / 1000;
+                row![
+                    button("\u{25C0}")
+                        .on_press_maybe((step > 0).then(|| Message::ScrubTo(step - 1))),
+                    slider(0..=last as u32, step as u32, |x| Message::ScrubTo(
+                        x as usize
+                    ))
+                    .width(400),
+                    button("\u{25B6}")
+                        .on_press_maybe((step < last).then(|| Message::ScrubTo(step + 1))),
+                    text(format!(
+                        "Step {} of {} at {}:{:02}",
+                        step + 1,
+                        last + 1,
+                        elapsed / 60,
+                        elapsed % 60
+                    )),
+                    button("Keep").on_press(Message::KeepReplay),
+                    button("Close").on_press(Message::CloseReplay)
+                ]
+                .spacing(10)
+                .align_y(iced::alignment::Vertical::Center)
+                .into()
+            }
+        };
+
+        row![picker, scrubber]
+            .spacing(20)
+            .padding(Padding::ZERO.left(10))
+            .align_y(iced::alignment::Vertical::Center)
+            .height(40)
+            .into()
+    }
+
+    fn view_team(&self) -> Element<'_, Message> {
         let name_header = container(text("Survivor Name")).align_bottom(30).width(125);
         let input_headers = row![
             container(text("Slippery Meat"))
-                .center_x(200)
+                .center_x(150)
                 .align_bottom(30),
             container(text("Up the Ante"))
-                .center_x(200)
+                .center_x(150)
+                .align_bottom(30),
+            container(text("Deliverance"))
+                .center_x(150)
                 .align_bottom(30),
+            container(text("Wicked")).center_x(150).align_bottom(30),
             container(text("Offering")).center_x(200).align_bottom(30),
-            container(text("Is Dead\nStatus")).center_x(120)
+            container(text("Condition\nMet")).center_x(100),
+            container(text("Is Dead\nStatus")).center_x(100)
         ];
         let output_headers = row![
             text("Attempt\nChance").width(120),
@@ -61,22 +212,29 @@ impl Calculator {
         .width(240);
         let column_headers = row![name_header, input_headers, output_headers].height(60);
 
-        rows = rows.push(column_headers);
-
-        let ids: Vec<SurvivorId> = (0..k::TEAM_MAX_CAPACITY)
-            .map(SurvivorId::try_new)
-            .collect::<Result<Vec<SurvivorId>, SurvivorIdError>>()
-            .expect(
-                "Can't observe a value >= max capacity in iterator below bound of max capacity.",
-            );
-
-        let make_name =
-            |player_id: SurvivorId| text(format!("Player {}", *player_id + 1)).width(125);
+        let make_name = |id: SurvivorId| {
+            let label = self.roster.get(id);
+            column![
+                text_input(&format!("Player {}", *id + 1), label.name())
+                    .on_input(move |x| Message::RenameSurvivor(id, x))
+                    .size(14),
+                text_input(
+                    "Character",
+                    label.character().as_deref().unwrap_or_default()
+                )
+                .on_input(move |x| Message::SetCharacter(id, x))
+                .size(12)
+            ]
+            .padding(Padding::ZERO.right(10))
+            .width(125)
+        };
         let make_input = |player_id| self.make_player(player_id);
         let make_output = |player_id: SurvivorId| -> Row<'_, Message> {
-            let (attempt_chance, total_chance) = self.widgets.odds.get(*player_id).expect(
-                "Generated id in range 0..TEAM_MAX_CAPACITY always less than TEAM_MAX_CAPACITY.",
-            );
+            let (attempt_chance, total_chance) = self
+                .widgets
+                .odds
+                .get(*player_id)
+                .expect("Team ids always have odds.");
             row![
                 container(text(attempt_chance.to_owned()))
                     .padding(Padding::ZERO.left(10))
@@ -86,62 +244,181 @@ impl Calculator {
                     .width(120)
             ]
         };
+        // The survivor chosen for quick entry stands out from the rest
         let make_row = |id: SurvivorId| {
-            container(row![make_name(id), make_input(id), make_output(id)]).height(50)
+            let selected = self.quick_survivor == Some(id);
+            container(column![
+                container(row![make_name(id), make_input(id), make_output(id)]).height(60),
+                self.make_distribution(id)
+            ])
+            .style(move |theme| match selected {
+                true => container::rounded_box(theme),
+                false => container::transparent(theme),
+            })
         };
 
-        ids.into_iter()
+        // Large lobbies scroll beneath the fixed column headers
+        let rows = self
+            .team
+            .ids()
             .map(make_row)
-            .fold(rows, Column::push)
+            .fold(Column::new(), Column::push);
+
+        column![column_headers, scrollable(rows).height(Length::Fill)].into()
+    }
+
+    /// The best tier of each luck perk and the offerings each survivor
+    /// owns, and how many perk slots they keep for perks of their own.
+    /// Suggestions only give survivors what they own.
+    fn view_ownership(&self) -> Element<'_, Message> {
+        const PERKS: [PerkName; 4] = [
+            PerkName::SlipperyMeat,
+            PerkName::UpTheAnte,
+            PerkName::Deliverance,
+            PerkName::Wicked,
+        ];
+        const CHALK: [Offering; 3] = [
+            Offering::ChalkPouch,
+            Offering::CreamPouch,
+            Offering::IvoryPouch,
+        ];
+        const SALT: [Offering; 3] = [
+            Offering::SaltPouch,
+            Offering::SaltStatuette,
+            Offering::SaltyLips,
+        ];
+
+        let column_headers = row![
+            container(text("Survivor Name")).align_bottom(30).width(125),
+            container(text("Slippery Meat"))
+                .center_x(130)
+                .align_bottom(30),
+            container(text("Up the Ante"))
+                .center_x(130)
+                .align_bottom(30),
+            container(text("Deliverance"))
+                .center_x(130)
+                .align_bottom(30),
+            container(text("Wicked")).center_x(130).align_bottom(30),
+            container(text("Offerings")).center_x(480).align_bottom(30),
+            container(text("Free Perk\nSlots")).center_x(100)
+        ]
+        .height(60);
+
+        let make_row = |id: SurvivorId| {
+            let owned = self.constraints.get(id);
+            let tiers = PERKS.map(|name| {
+                let best = owned.best_tier(name);
+                let input: Element<'_, Message> = match name.is_conditional_lock() {
+                    true => checkbox("", best.is_some())
+                        .on_toggle(move |x| Message::SetOwnedTier(id, name, x.then_some(Tier::One)))
+                        .into(),
+                    false => pick_list(
+                        TierSlotDisplay::all(),
+                        Some(TierSlotDisplay(best)),
+                        move |TierSlotDisplay(x)| Message::SetOwnedTier(id, name, x),
+                    )
+                    .width(100)
+                    .into(),
+                };
+                container(input).center_x(130).into()
+            });
+            let offerings = |offerings: [Offering; 3]| {
+                let boxes = offerings.map(|x| {
+                    let label = OfferingSlotDisplay(OfferingSlot::new(Some(x))).to_string();
+                    checkbox(label, owned.owns_offering(x))
+                        .on_toggle(move |owned| Message::SetOwnsOffering(id, x, owned))
+                        .size(14)
+                        .text_size(12)
+                        .width(160)
+                        .into()
+                });
+                Row::with_children(boxes)
+            };
+            row![
+                text(self.roster.display_name(id)).width(125),
+                Row::with_children(tiers),
+                column![offerings(CHALK), offerings(SALT)].spacing(4),
+                container(pick_list(
+                    (0..=k::MAX_PERKS).collect::<Vec<_>>(),
+                    Some(owned.kept_free_slots()),
+                    move |x| Message::SetKeptFreeSlots(id, x)
+                ))
+                .center_x(100)
+            ]
+            .align_y(iced::alignment::Vertical::Center)
+            .height(60)
+        };
+
+        let rows = self
+            .team
+            .ids()
+            .map(make_row)
+            .fold(Column::new(), Column::push);
+
+        column![column_headers, scrollable(rows).height(Length::Fill)].into()
+    }
+
+    /// The best upgrades beside the chart of how fast each survivor's odds build up
+    fn view_bottom(&self) -> Element<'_, Message> {
+        row![
+            container(self.view_upgrades()).width(Length::Fill),
+            self.view_chart()
+        ]
+        .height(CHART_HEIGHT)
+        .into()
+    }
+
+    fn view_chart(&self) -> Element<'_, Message> {
+        let chart = canvas(EscapeChart {
+            curves: &self.widgets.curves,
+            baseline: &self.widgets.baseline,
+        })
+        .width(CHART_WIDTH)
+        .height(Length::Fill);
+
+        let names = self
+            .team
+            .ids()
+            .zip(SURVIVOR_COLORS)
+            .map(|(id, color)| text(self.roster.display_name(id)).size(12).color(color));
+        let legend = std::iter::once(text("No luck items").size(12).color(BASELINE_COLOR))
+            .chain(names)
+            .fold(Column::new().push(text("By Attempt")), Column::push)
+            .spacing(2)
+            .width(140);
+
+        row![chart, legend].spacing(10).into()
+    }
+
+    fn view_upgrades(&self) -> Element<'_, Message> {
+        let lines = match self.widgets.upgrades.is_empty() {
+            true => vec![String::from("Nothing would improve the team's odds.")],
+            false => self
+                .widgets
+                .upgrades
+                .iter()
+                .map(|(id, line)| format!("{}: {line}", self.roster.display_name(*id)))
+                .collect(),
+        };
+
+        lines
+            .into_iter()
+            .map(|line| text(line).size(14))
+            .fold(Column::new().push(text("Best Upgrades")), Column::push)
+            .spacing(2)
+            .padding(Padding::ZERO.left(10))
             .into()
     }
 
-    fn make_player(&self, id: SurvivorId) -> Element<Message> {
+    fn make_player(&self, id: SurvivorId) -> Element<'_, Message> {
         let player = self.team.get_player(id);
 
         row![
-            container(
-                combo_box(
-                    &self.widgets.tier_choices,
-                    "",
-                    Some(&TierSlotDisplay(
-                        player.get_perk_tier(PerkName::SlipperyMeat).cloned()
-                    )),
-                    move |TierSlotDisplay(x)| {
-                        Message::UpdateSurvivor(
-                            SurvivorUpdate::perk()
-                                .id(id)
-                                .perk(PerkName::SlipperyMeat)
-                                .tier(TierSlot::new(x))
-                                .call(),
-                        )
-                    }
-                )
-                .width(120)
-            )
-            .padding(Padding::ZERO.left(10))
-            .center_x(200),
-            container(
-                combo_box(
-                    &self.widgets.tier_choices,
-                    "",
-                    Some(&TierSlotDisplay(
-                        player.get_perk_tier(PerkName::UpTheAnte).cloned()
-                    )),
-                    move |TierSlotDisplay(x)| {
-                        Message::UpdateSurvivor(
-                            SurvivorUpdate::perk()
-                                .id(id)
-                                .perk(PerkName::UpTheAnte)
-                                .tier(TierSlot::new(x))
-                                .call(),
-                        )
-                    }
-                )
-                .width(120)
-            )
-            .padding(Padding::ZERO.left(12))
-            .center_x(200),
+            self.make_perk_input(id, PerkName::SlipperyMeat),
+            self.make_perk_input(id, PerkName::UpTheAnte),
+            self.make_perk_input(id, PerkName::Deliverance),
+            self.make_perk_input(id, PerkName::Wicked),
             container(
                 combo_box(
                     &self.widgets.offering_choices,
@@ -160,13 +437,136 @@ impl Calculator {
             )
             .padding(Padding::ZERO.left(23))
             .center_x(200),
+            container(
+                checkbox("", player.lock_condition_met()).on_toggle(move |x| {
+                    Message::UpdateSurvivor(SurvivorUpdate::lock_condition().id(id).met(x).call())
+                })
+            )
+            .center_x(100),
             container(
                 checkbox("", player.is_dead()).on_toggle(move |x| Message::UpdateSurvivor(
                     SurvivorUpdate::living_status().id(id).alive(!x).call()
                 ))
             )
-            .center_x(120)
+            .center_x(100)
         ]
         .into()
     }
+
+    /// A table of the chance of escaping on exactly, and by, each attempt
+    fn make_distribution(&self, id: SurvivorId) -> Element<'_, Message> {
+        let dist = self
+            .widgets
+            .distributions
+            .get(*id)
+            .expect("Team ids always have distributions.");
+        let cell = |s: String| text(s).size(12).width(70);
+        let table_row = |label: &str, cells: Vec<String>, last: String| {
+            std::iter::once(label.to_owned())
+                .chain(cells)
+                .chain(std::iter::once(last))
+                .map(cell)
+                .fold(Row::new(), Row::push)
+        };
+
+        let attempts = (1..=dist.exactly.len()).map(|x| x.to_string()).collect();
+        let table = column![
+            table_row("Attempt", attempts, "Never".into()),
+            table_row("Exactly", dist.exactly.clone(), dist.never.clone()),
+            table_row("By", dist.by.clone(), String::new())
+        ];
+
+        // The breakdown of where the survivor's luck came from opens beneath the table
+        let expanded = self.expanded_breakdowns.contains(&id);
+        let toggle = button(
+            text(if expanded {
+                "\u{25BE} Luck"
+            } else {
+                "\u{25B8} Luck"
+            })
+            .size(12),
+        )
+        .style(button::text)
+        .on_press(Message::ToggleBreakdown(id));
+        let details = match expanded {
+            true => Some(self.make_breakdown(id)),
+            false => None,
+        };
+
+        row![
+            container(toggle).width(125),
+            column![table].push_maybe(details).spacing(4)
+        ]
+        .padding(Padding::ZERO.bottom(8))
+        .into()
+    }
+
+    /// Each source of the survivor's luck on one attempt, and how much it gives
+    fn make_breakdown(&self, id: SurvivorId) -> Element<'_, Message> {
+        let breakdown = self
+            .widgets
+            .breakdowns
+            .get(*id)
+            .expect("Team ids always have breakdowns.");
+        let line = |(source, luck): (String, String)| {
+            row![text(source).size(12).width(280), text(luck).size(12)]
+        };
+
+        breakdown
+            .contributions()
+            .iter()
+            .map(|x| {
+                (
+                    describe_source(x.source(), id, &self.roster),
+                    format_signed_percent(x.luck()),
+                )
+            })
+            .map(line)
+            .fold(Column::new(), Column::push)
+            .into()
+    }
+
+    /// A tier box for a perk, or an equipped box for a lock perk, whose
+    /// tier changes nothing
+    fn make_perk_input(&self, id: SurvivorId, perk: PerkName) -> Container<'_, Message> {
+        let tier = self.team.get_player(id).get_perk_tier(perk).cloned();
+        let update = move |x| {
+            Message::UpdateSurvivor(
+                SurvivorUpdate::perk()
+                    .id(id)
+                    .perk(perk)
+                    .tier(TierSlot::new(x))
+                    .call(),
+            )
+        };
+
+        if perk.is_conditional_lock() {
+            return container(
+                checkbox("", tier.is_some()).on_toggle(move |x| update(x.then_some(Tier::One))),
+            )
+            .center_x(150);
+        }
+        container(
+            combo_box(
+                &self.widgets.tier_choices,
+                "",
+                Some(&TierSlotDisplay(tier)),
+                move |TierSlotDisplay(x)| update(x),
+            )
+            .width(110),
+        )
+        .center_x(150)
+    }
+}
+
+/// The quick entry keys, in one line beneath everything else
+fn view_legend<'a>() -> Element<'a, Message> {
+    let legend = QUICK_KEYS
+        .iter()
+        .map(|(key, action)| format!("{key}: {action}"))
+        .collect::<Vec<_>>()
+        .join("   ");
+    container(text(legend).size(12))
+        .padding(Padding::ZERO.left(10).bottom(4))
+        .into()
 }
@@ -3,51 +3,237 @@ use std::borrow::Cow;
 use iced::widget::combo_box;
 
 use hook_escape_calculator::{
+    Luck,
+    constants::misc as k,
+    distribution::{ApproximateDistribution, EscapeDistribution},
     offering::{Offering, OfferingSlot},
-    perk, team,
+    optimizer::Objective,
+    patch::{self, GameVersion},
+    perk,
+    provenance::{LuckBreakdown, LuckItem, LuckSource},
+    roster::Roster,
+    ruleset::Ruleset,
+    team::{self, TeamSize},
+    update::{LoadoutUpdate, SurvivorId, SurvivorUpdate, SurvivorUpdateData},
 };
+use num_traits::{Signed, ToPrimitive};
+
+use crate::presets::PresetStore;
 
 #[derive(Debug, Clone)]
 pub struct WidgetData {
     pub tier_choices: combo_box::State<TierSlotDisplay>,
     pub offering_choices: combo_box::State<OfferingSlotDisplay>,
+    pub rules_choices: Vec<RulesChoice>,
+    pub objective_choices: Vec<ObjectiveChoice>,
+    pub size_choices: Vec<TeamSize>,
+    pub preset_names: Vec<String>,
+    /// Saved sessions, newest first
+    pub session_names: Vec<String>,
     pub odds: Vec<(String, String)>,
+    pub distributions: Vec<DistributionText>,
+    /// Each survivor's chance of having escaped by each attempt, for the chart
+    pub curves: Vec<Vec<f64>>,
+    /// The same chance for a survivor with no luck items
+    pub baseline: Vec<f64>,
+    /// Where each survivor's luck on one attempt comes from
+    pub breakdowns: Vec<LuckBreakdown>,
+    /// The most valuable single changes to the team, best first, each
+    /// beside the survivor it changes
+    pub upgrades: Vec<(SurvivorId, String)>,
 }
 
 impl WidgetData {
-    pub fn from_team(team: &team::Team) -> Self {
+    pub fn from_team(
+        team: &team::Team,
+        rules: &Ruleset,
+        has_rules_file: bool,
+        presets: &PresetStore,
+        session_names: Vec<String>,
+    ) -> Self {
         let tier_choices = TierSlotDisplay::total_combo_box();
         let offering_choices = OfferingSlotDisplay::total_combo_box();
-        let odds = Self::make_odds(team);
+        let rules_choices = RulesChoice::all(has_rules_file);
+        let odds = Self::make_odds(team, rules);
+        let distributions = Self::make_distributions(team, rules);
+        let (curves, baseline) = Self::make_curves(team, rules);
+        let upgrades = Self::make_upgrades(team, rules);
         Self {
+            breakdowns: team.luck_breakdowns(rules),
             tier_choices,
             offering_choices,
+            rules_choices,
+            objective_choices: ObjectiveChoice::all(team),
+            size_choices: (1..=k::TEAM_MAX_CAPACITY)
+                .map(|x| TeamSize::try_new(x).expect("Range up to capacity is a valid size."))
+                .collect(),
+            preset_names: presets.names(),
+            session_names,
             odds,
+            distributions,
+            curves,
+            baseline,
+            upgrades,
         }
     }
 
-    pub fn renew_odds(&mut self, team: &team::Team) {
-        self.odds = Self::make_odds(team);
+    pub fn renew_presets(&mut self, presets: &PresetStore) {
+        self.preset_names = presets.names();
+    }
+
+    /// Offers an objective for each survivor in the team
+    pub fn renew_team_size(&mut self, team: &team::Team) {
+        self.objective_choices = ObjectiveChoice::all(team);
     }
 
-    fn make_odds(team: &team::Team) -> Vec<(String, String)> {
-        let f = |num: f64| {
-            let num = num * 100.;
-            format!("{num:.2}%")
-        };
+    pub fn renew_odds(&mut self, team: &team::Team, rules: &Ruleset) {
+        self.odds = Self::make_odds(team, rules);
+        self.distributions = Self::make_distributions(team, rules);
+        (self.curves, self.baseline) = Self::make_curves(team, rules);
+        self.breakdowns = team.luck_breakdowns(rules);
+        self.upgrades = Self::make_upgrades(team, rules);
+    }
 
-        team.luck_output()
+    fn make_odds(team: &team::Team, rules: &Ruleset) -> Vec<(String, String)> {
+        team.luck_output(rules)
             .into_iter()
-            .map(|(num1, num2)| (f(num1), f(num2)))
+            .map(|(num1, num2)| (format_percent(num1), format_percent(num2)))
+            .collect()
+    }
+
+    fn make_distributions(team: &team::Team, rules: &Ruleset) -> Vec<DistributionText> {
+        team.escape_distributions(rules)
+            .iter()
+            .map(|x| DistributionText::new(&x.approximate()))
             .collect()
     }
+
+    /// The team's curves, and the curve of a survivor in a team of the
+    /// same size where nobody brings a luck item
+    fn make_curves(team: &team::Team, rules: &Ruleset) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let by = |x: &EscapeDistribution| x.approximate().by().clone();
+        let curves = team.escape_distributions(rules).iter().map(by).collect();
+        let baseline = team::Team::with_size(team.size())
+            .escape_distributions(rules)
+            .first()
+            .map(by)
+            .unwrap_or_default();
+        (curves, baseline)
+    }
 }
 
-type TierSlot = Option<perk::Tier>;
+const SHOWN_UPGRADES: usize = 5;
+
+impl WidgetData {
+    fn make_upgrades(team: &team::Team, rules: &Ruleset) -> Vec<(SurvivorId, String)> {
+        team.marginal_values(rules)
+            .into_iter()
+            .filter(|x| x.average_change().is_positive())
+            .take(SHOWN_UPGRADES)
+            .map(|x| {
+                let change = x
+                    .average_change()
+                    .to_f64()
+                    .expect("A probability is always within the range of f64.");
+                let line = format!(
+                    "{} (+{} team average)",
+                    describe_update(x.update()),
+                    format_percent(change)
+                );
+                (*x.update().id(), line)
+            })
+            .collect()
+    }
+}
+
+/// Names the item an update equips, as the combo boxes write it
+fn describe_update(update: &SurvivorUpdate) -> String {
+    let description = match update.update() {
+        SurvivorUpdateData::LoadoutUpdate(LoadoutUpdate::Perk(x)) => match x.value().into_inner() {
+            Some(tier) => format!("{} {tier}", x.perk()),
+            None => format!("remove {}", x.perk()),
+        },
+        SurvivorUpdateData::LoadoutUpdate(LoadoutUpdate::Offering(x)) => {
+            OfferingSlotDisplay(*x).to_string()
+        }
+        _ => String::from("other change"),
+    };
+    description.to_uppercase()
+}
+
+/// Names a source of a survivor's luck. Items of the survivor's own are
+/// told apart from those of their team mates.
+pub fn describe_source(source: &LuckSource, id: SurvivorId, roster: &Roster) -> String {
+    let whose = |owner: &Option<SurvivorId>| match owner {
+        Some(owner) if *owner == id => String::from("Own "),
+        Some(owner) => format!("{}'s ", roster.display_name(*owner)),
+        None => String::new(),
+    };
+    match source {
+        LuckSource::Base => String::from("Base chance"),
+        LuckSource::Item { owner, item } => {
+            let item = match item {
+                LuckItem::Perk(perk) => format!("{} {}", perk.name(), perk.tier()),
+                LuckItem::Offering(offering) => offering.to_string(),
+            };
+            format!("{}{}", whose(owner), item.to_uppercase())
+        }
+        LuckSource::UpTheAnte {
+            owner,
+            living_others,
+        } => format!(
+            "{}UP THE ANTE \u{00D7} {living_others} other living",
+            whose(owner)
+        ),
+        LuckSource::Lock(perk) => format!("{} lock", perk.to_string().to_uppercase()),
+        LuckSource::Cap => String::from("Beyond certainty"),
+        LuckSource::Untraced => String::from("Other"),
+    }
+}
+
+pub fn format_signed_percent(luck: &Luck) -> String {
+    let num = luck
+        .to_f64()
+        .expect("A probability is always within the range of f64.")
+        * 100.;
+    format!("{num:+.2}%")
+}
+
+fn format_percent(num: f64) -> String {
+    let num = num * 100.;
+    format!("{num:.2}%")
+}
+
+/// A survivor's escape distribution, written out for the table under their row
 #[derive(Debug, Clone)]
+pub struct DistributionText {
+    pub exactly: Vec<String>,
+    pub by: Vec<String>,
+    pub never: String,
+}
+
+impl DistributionText {
+    fn new(dist: &ApproximateDistribution) -> Self {
+        let f = |xs: &[f64]| xs.iter().copied().map(format_percent).collect();
+        Self {
+            exactly: f(dist.exactly()),
+            by: f(dist.by()),
+            never: format_percent(dist.never()),
+        }
+    }
+}
+
+type TierSlot = Option<perk::Tier>;
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TierSlotDisplay(pub TierSlot);
 
 impl TierSlotDisplay {
+    /// No tier, then every tier from the lowest
+    pub fn all() -> Vec<Self> {
+        std::iter::once(TierSlotDisplay(None))
+            .chain(perk::Tier::iterator().map(|x| TierSlotDisplay(Some(x))))
+            .collect()
+    }
     pub fn total_combo_box() -> combo_box::State<Self> {
         combo_box::State::new(
             perk::Tier::iterator()
@@ -93,3 +279,61 @@ impl std::fmt::Display for OfferingSlotDisplay {
         write!(f, "{}", &s)
     }
 }
+
+/// Where the rules of the calculation come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RulesChoice {
+    Live,
+    Patch(GameVersion),
+    File,
+}
+
+impl RulesChoice {
+    /// Every choice, newest patch first
+    fn all(has_rules_file: bool) -> Vec<Self> {
+        std::iter::once(RulesChoice::Live)
+            .chain(patch::versions().rev().map(RulesChoice::Patch))
+            .chain(has_rules_file.then_some(RulesChoice::File))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for RulesChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RulesChoice::Live => write!(f, "Live Game"),
+            RulesChoice::Patch(version) => write!(f, "Patch {version}"),
+            RulesChoice::File => write!(f, "Rules File"),
+        }
+    }
+}
+
+/// What the Suggest button optimizes for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectiveChoice(pub Objective);
+
+impl ObjectiveChoice {
+    fn all(team: &team::Team) -> Vec<Self> {
+        [Objective::Average, Objective::Minimum]
+            .into_iter()
+            .chain(team.ids().map(Objective::Survivor))
+            .map(ObjectiveChoice)
+            .collect()
+    }
+}
+
+impl std::default::Default for ObjectiveChoice {
+    fn default() -> Self {
+        ObjectiveChoice(Objective::Average)
+    }
+}
+
+impl std::fmt::Display for ObjectiveChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Objective::Average => write!(f, "Team Average"),
+            Objective::Minimum => write!(f, "Weakest Survivor"),
+            Objective::Survivor(id) => write!(f, "Player {}", *id + 1),
+        }
+    }
+}
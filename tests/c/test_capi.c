@@ -0,0 +1,121 @@
+/*
+ * Exercises the C API of the `capi` feature. From the repository root:
+ *
+ *     cargo build --lib --features capi
+ *     cc tests/c/test_capi.c -Iinclude -Ltarget/debug -lhook_escape_calculator -lm -o test_capi
+ *     LD_LIBRARY_PATH=target/debug ./test_capi
+ */
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "hook_escape_calculator.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                       \
+    do {                                                                       \
+        if (!(condition)) {                                                    \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__,  \
+                    #condition);                                               \
+            failures++;                                                        \
+        }                                                                      \
+    } while (0)
+
+#define CHECK_STATUS(call, expected)                                           \
+    do {                                                                       \
+        HecStatus status_ = (call);                                            \
+        if (status_ != (expected)) {                                           \
+            fprintf(stderr, "%s:%d: %s gave \"%s\"\n", __FILE__, __LINE__,     \
+                    #call, hec_status_message(status_));                       \
+            failures++;                                                        \
+        }                                                                      \
+    } while (0)
+
+static int close_to(double a, double b) { return fabs(a - b) < 1e-9; }
+
+/* The chance of escaping on any of the attempts */
+static double total_chance(double single, int attempts) {
+    return 1 - pow(1 - single, attempts);
+}
+
+static void test_known_odds(void) {
+    HecTeam *team = NULL;
+    double single[4];
+    double total[4];
+    size_t size = 0;
+
+    CHECK_STATUS(hec_team_new(4, &team), HEC_STATUS_OK);
+    CHECK_STATUS(hec_team_size(team, &size), HEC_STATUS_OK);
+    CHECK(size == 4);
+
+    /* Up the Ante gives everyone 3% for each of the three others */
+    CHECK_STATUS(hec_team_set_perk(team, 0, HEC_PERK_UP_THE_ANTE, 3), HEC_STATUS_OK);
+    CHECK_STATUS(hec_team_odds(team, single, total, 4), HEC_STATUS_OK);
+    for (int i = 0; i < 4; i++) {
+        CHECK(close_to(single[i], 0.13));
+        CHECK(close_to(total[i], total_chance(0.13, 3)));
+    }
+
+    /* A death leaves two others, and Slippery Meat adds personal luck and attempts */
+    CHECK_STATUS(hec_team_set_alive(team, 3, false), HEC_STATUS_OK);
+    CHECK_STATUS(hec_team_set_perk(team, 1, HEC_PERK_SLIPPERY_MEAT, 3), HEC_STATUS_OK);
+    CHECK_STATUS(hec_team_set_offering(team, 2, HEC_OFFERING_IVORY_POUCH), HEC_STATUS_OK);
+    CHECK_STATUS(hec_team_odds(team, single, total, 4), HEC_STATUS_OK);
+    CHECK(close_to(single[0], 0.10));
+    CHECK(close_to(single[1], 0.14));
+    CHECK(close_to(total[1], total_chance(0.14, 6)));
+    CHECK(close_to(single[2], 0.13));
+
+    hec_team_free(team);
+}
+
+static void test_errors_leave_team_unchanged(void) {
+    HecTeam *team = NULL;
+    double before[1], after[1], total[1];
+
+    CHECK_STATUS(hec_team_new(9, &team), HEC_STATUS_INVALID_SIZE);
+    CHECK_STATUS(hec_team_new(1, &team), HEC_STATUS_OK);
+    CHECK_STATUS(hec_team_odds(team, before, total, 1), HEC_STATUS_OK);
+
+    CHECK_STATUS(hec_team_set_perk(team, 0, 42, 1), HEC_STATUS_INVALID_PERK);
+    CHECK_STATUS(hec_team_set_perk(team, 0, HEC_PERK_WICKED, 4), HEC_STATUS_INVALID_TIER);
+    CHECK_STATUS(hec_team_set_offering(team, 0, 42), HEC_STATUS_INVALID_OFFERING);
+    CHECK_STATUS(hec_team_set_lock_condition(team, 1, true), HEC_STATUS_NOT_IN_TEAM);
+    CHECK_STATUS(hec_team_set_alive(NULL, 0, false), HEC_STATUS_NULL_POINTER);
+    CHECK_STATUS(hec_team_odds(team, after, total, 0), HEC_STATUS_BUFFER_TOO_SMALL);
+
+    CHECK_STATUS(hec_team_odds(team, after, total, 1), HEC_STATUS_OK);
+    CHECK(before[0] == after[0]);
+
+    hec_team_free(team);
+    hec_team_free(NULL);
+}
+
+static void test_met_condition_locks_luck(void) {
+    HecTeam *team = NULL;
+    double single[2], total[2];
+
+    CHECK_STATUS(hec_team_new(2, &team), HEC_STATUS_OK);
+    CHECK_STATUS(hec_team_set_perk(team, 1, HEC_PERK_DELIVERANCE, 1), HEC_STATUS_OK);
+    CHECK_STATUS(hec_team_set_lock_condition(team, 1, true), HEC_STATUS_OK);
+    CHECK_STATUS(hec_team_odds(team, single, total, 2), HEC_STATUS_OK);
+    CHECK(close_to(single[1], 1.0));
+    CHECK(close_to(total[1], 1.0));
+    CHECK(close_to(single[0], 0.04));
+
+    hec_team_free(team);
+}
+
+int main(void) {
+    test_known_odds();
+    test_errors_leave_team_unchanged();
+    test_met_condition_locks_luck();
+
+    if (failures > 0) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return EXIT_FAILURE;
+    }
+    puts("all checks passed");
+    return EXIT_SUCCESS;
+}
@@ -0,0 +1,79 @@
+"""Checks the Python module against scenarios worked out by hand.
+
+Build the module into the current environment with `maturin develop`,
+then run `python -m unittest discover tests/python`.
+"""
+
+import unittest
+
+from hook_escape_calculator import Offering, PerkName, Team, Tier
+
+
+def total(single, attempts):
+    """The chance of escaping on any of the attempts"""
+    return 1 - (1 - single) ** attempts
+
+
+class KnownScenarios(unittest.TestCase):
+    def assert_odds(self, odds, expected):
+        self.assertEqual(len(odds), len(expected))
+        for (single, total_chance), (want_single, want_total) in zip(odds, expected):
+            self.assertAlmostEqual(single, want_single)
+            self.assertAlmostEqual(total_chance, want_total)
+
+    def test_empty_team_has_base_odds(self):
+        self.assert_odds(Team().luck_output(), [(0.04, total(0.04, 3))] * 4)
+
+    def test_up_the_ante_counts_the_other_living_survivors(self):
+        team = Team()
+        team.set_perk(0, PerkName.UpTheAnte, Tier.Three)
+        # 3% for each of the three others, given to everyone
+        self.assert_odds(team.luck_output(), [(0.13, total(0.13, 3))] * 4)
+
+        team.set_alive(3, False)
+        self.assert_odds(team.luck_output()[:3], [(0.10, total(0.10, 3))] * 3)
+
+    def test_slippery_meat_is_personal_and_adds_attempts(self):
+        team = Team(2)
+        team.set_perk(0, PerkName.SlipperyMeat, Tier.Three)
+        team.set_offering(1, Offering.SaltyLips)
+        self.assert_odds(
+            team.luck_output(),
+            [(0.11, total(0.11, 6)), (0.07, total(0.07, 3))],
+        )
+
+    def test_met_condition_locks_luck(self):
+        team = Team(1)
+        team.set_perk(0, PerkName.Deliverance, Tier.One)
+        team.set_lock_condition(0, True)
+        self.assert_odds(team.luck_output(), [(1.0, 1.0)])
+
+    def test_odds_as_dicts(self):
+        team = Team(3)
+        team.set_offering(2, Offering.ChalkPouch)
+        self.assertEqual(
+            team.odds(),
+            [{"single": s, "total": t} for s, t in team.luck_output()],
+        )
+
+    def test_codes_round_trip(self):
+        team = Team(5)
+        team.set_perk(4, PerkName.Wicked, Tier.Two)
+        team.set_offering(4, Offering.IvoryPouch)
+        self.assertEqual(Team.from_code(team.to_code()), team)
+
+    def test_refused_updates_raise(self):
+        team = Team(2)
+        with self.assertRaises(ValueError):
+            Team(9)
+        with self.assertRaises(ValueError):
+            team.set_alive(2, False)
+        with self.assertRaises(ValueError):
+            team.set_alive(8, False)
+        with self.assertRaises(ValueError):
+            Team.from_code("not a code")
+        self.assertEqual(team, Team(2))
+
+
+if __name__ == "__main__":
+    unittest.main()